@@ -1,80 +1,149 @@
-#![feature(test)]
-
-extern crate test;
-use criterion::{criterion_group, criterion_main, Criterion};
+use criterion::Criterion;
+use std::process::Command;
 #[cfg(not(feature = "async"))]
 use std::time::Duration;
 #[cfg(feature = "async")]
 use tokio::time::Duration;
 
+/// Set in the child processes to the configuration to measure.
+/// The logger is initialized once per process, so each configuration runs in its own process.
+const CONFIG: &str = "LAYLA_LOG_BENCH_CONFIG";
+
+/// The configurations measured side by side: the unbuffered baseline and the buffered writes.
+const CONFIGS: [&str; 2] = ["unbuffered", "buffered"];
+
+/// The setting of the configuration.
+fn setting(config: &str) -> layla_log::Setting {
+    layla_log::Setting {
+        single_length: 1219,
+        buffer_size: if config == "buffered" { 1 << 16 } else { 0 },
+        ..Default::default()
+    }
+}
+
+fn criterion() -> Criterion {
+    Criterion::default()
+        .measurement_time(Duration::from_secs(100))
+        .configure_from_args()
+}
+
 #[cfg(feature = "async")]
 mod bench {
-    use criterion::Criterion;
-    use layla_log::{clean_log, info, init, Setting};
+    use criterion::{BatchSize, Criterion};
+    use layla_log::{clean_log, flush, info, init};
 
     fn rt() -> tokio::runtime::Runtime {
         tokio::runtime::Builder::new_multi_thread()
             .worker_threads(1)
+            .enable_all()
             .build()
             .unwrap()
     }
 
-    pub fn write_a_lot(c: &mut Criterion) {
+    pub fn run(c: &mut Criterion, config: &str) {
         let rt = rt();
+        rt.block_on(init(super::setting(config)));
 
-        c.bench_function("write_a_lot", |b| {
-            b.iter(|| {
-                let task = || async {
-                    init(Setting {
-                        single_length: 1219,
-                        ..Default::default()
-                    }).await;
-                    clean_log().await;
-                    let mut handles = Vec::new();
-                    for _ in 0..10_000 {
-                        handles.push(tokio::spawn(async { info!("Hello world"); }))
-                    }
-                    for handle in handles {
-                        handle.await.unwrap();
-                    }
-                };
+        c.benchmark_group("write_a_lot")
+            .bench_function(config, |b| {
+                b.iter_batched(
+                    || rt.block_on(clean_log()),
+                    |()| {
+                        rt.block_on(async {
+                            let mut handles = Vec::new();
+                            for _ in 0..10_000 {
+                                handles.push(tokio::spawn(async {
+                                    info!("Hello world");
+                                }))
+                            }
+                            for handle in handles {
+                                handle.await.unwrap();
+                            }
+                            flush().await;
+                        })
+                    },
+                    BatchSize::PerIteration,
+                )
+            });
 
-                rt.block_on(task());
-            })
-        });
+        c.benchmark_group("write_in_order")
+            .bench_function(config, |b| {
+                b.iter_batched(
+                    || rt.block_on(clean_log()),
+                    |()| {
+                        rt.block_on(async {
+                            for _ in 0..10_000 {
+                                info!("Hello world");
+                            }
+                            flush().await;
+                        })
+                    },
+                    BatchSize::PerIteration,
+                )
+            });
     }
 }
 
 #[cfg(not(feature = "async"))]
 mod bench {
-    use criterion::Criterion;
-    use layla_log::{clean_log, info, init, Setting};
+    use criterion::{BatchSize, Criterion};
+    use layla_log::{clean_log, flush, info, init};
+
+    pub fn run(c: &mut Criterion, config: &str) {
+        init(super::setting(config));
 
-    pub fn write_a_lot(c: &mut Criterion) {
-        c.bench_function("write_a_lot", |b| {
-            b.iter(|| {
-                init(Setting {
-                    single_length: 1219,
-                    ..Default::default()
-                });
-                clean_log();
-                let mut handles = Vec::new();
-                for _ in 0..10_000 {
-                    handles.push(std::thread::spawn(|| {
-                        info!("Hello, world!");
-                    }))
-                }
-                for handle in handles {
-                    handle.join().unwrap();
-                }
-            })
-        });
+        c.benchmark_group("write_a_lot")
+            .bench_function(config, |b| {
+                b.iter_batched(
+                    clean_log,
+                    |()| {
+                        let mut handles = Vec::new();
+                        for _ in 0..10_000 {
+                            handles.push(std::thread::spawn(|| {
+                                info!("Hello, world!");
+                            }))
+                        }
+                        for handle in handles {
+                            handle.join().unwrap();
+                        }
+                        flush();
+                    },
+                    BatchSize::PerIteration,
+                )
+            });
+
+        c.benchmark_group("write_in_order")
+            .bench_function(config, |b| {
+                b.iter_batched(
+                    clean_log,
+                    |()| {
+                        for _ in 0..10_000 {
+                            info!("Hello, world!");
+                        }
+                        flush();
+                    },
+                    BatchSize::PerIteration,
+                )
+            });
     }
 }
 
-criterion_group!(
-    name = benches;
-    config = Criterion::default().measurement_time(Duration::from_secs(100));
-    targets = bench::write_a_lot
-);
-criterion_main!(benches);
+/// Run the benches of each configuration in a child process with the same arguments,
+/// so that the buffered writes are reported next to the unbuffered ones.
+fn main() {
+    if let Ok(config) = std::env::var(CONFIG) {
+        let mut c = criterion();
+        bench::run(&mut c, &config);
+        c.final_summary();
+        return;
+    }
+
+    for config in CONFIGS {
+        let status = Command::new(std::env::current_exe().unwrap())
+            .args(std::env::args().skip(1))
+            .env(CONFIG, config)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+}
@@ -0,0 +1,33 @@
+//! Procedural macros for [layla-log](https://docs.rs/layla-log), enabled by its `macros` feature.
+
+mod fields;
+mod logged;
+
+use proc_macro::TokenStream;
+
+/// Log the entry of the function with its arguments, and the exit with the return value (or the `Err`) and the elapsed time.
+///
+/// - `level = "debug"`: the level of the logs, `debug` by default. An `Err` is always logged as an error.
+/// - `skip(password, ..)`: the arguments not to log.
+///
+/// The logged arguments and the return value need to implement [`Debug`]. Works on both `fn` and `async fn`.
+#[proc_macro_attribute]
+pub fn logged(attr: TokenStream, item: TokenStream) -> TokenStream {
+    logged::expand(attr.into(), item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derive `LogFields` for a struct with named fields, to log it as `key=value` pairs by `info!(value; "message")`.
+///
+/// - `#[log(skip)]`: the field is not logged.
+/// - `#[log(redact)]`: the value is logged as `***`.
+/// - `#[log(rename = "key")]`: the field is logged with the key.
+///
+/// The other fields are formatted by [`Debug`].
+#[proc_macro_derive(LogFields, attributes(log))]
+pub fn derive_log_fields(item: TokenStream) -> TokenStream {
+    fields::expand(item.into())
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
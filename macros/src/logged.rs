@@ -0,0 +1,264 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, ToTokens};
+use syn::visit_mut::{self, VisitMut};
+use syn::{
+    parse_quote, Expr, ExprBreak, ExprMatch, FnArg, Ident, Item, ItemFn, Lifetime, LitStr, Pat,
+    ReturnType, Type,
+};
+
+/// Parse the name of a level into the path of the variant.
+pub(crate) fn parse_level(name: &LitStr) -> syn::Result<TokenStream> {
+    let variant = match name.value().trim().to_lowercase().as_str() {
+        "trace" => quote!(Trace),
+        "debug" => quote!(Debug),
+        "info" => quote!(Info),
+        "warn" => quote!(Warn),
+        "error" => quote!(Error),
+        "fatal" => quote!(Fatal),
+        _ => return Err(syn::Error::new_spanned(
+            name,
+            "unknown level, expected one of `trace`, `debug`, `info`, `warn`, `error` and `fatal`",
+        )),
+    };
+    Ok(quote!(::layla_log::LogLevel::#variant))
+}
+
+/// Whether the return type is a `Result`, such as `Result<T, E>` or `io::Result<T>`.
+fn is_result(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Result"),
+        _ => false,
+    }
+}
+
+/// Whether the return type is an `Option`.
+fn is_option(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Option"),
+        _ => false,
+    }
+}
+
+/// Whether the return type is `()`.
+fn is_unit(ty: &Type) -> bool {
+    matches!(ty, Type::Tuple(tuple) if tuple.elems.is_empty())
+}
+
+/// Whether the return type contains `impl Trait`, which cannot be written as the type of a binding.
+fn has_impl_trait(ty: &Type) -> bool {
+    ty.to_token_stream()
+        .into_iter()
+        .any(|token| token.to_string() == "impl")
+}
+
+/// What `?` returns from the function.
+#[derive(Clone, Copy)]
+enum Early {
+    /// the `Err`, converted by `From`.
+    Err,
+    /// `None`.
+    None,
+    /// unknown, so `?` is left as it is, and returns without the exit log.
+    Unknown,
+}
+
+/// Rewrite `return` and `?` in the body to break out of the labeled block of the body, so that the exit is logged.
+/// The body stays in the function instead of a closure, so it can return the borrows of the arguments, such as `&mut self`.
+/// The closures, async blocks and items in the body have their own `return` and `?`, so they are not visited.
+struct BreakOut {
+    /// the label of the block of the body.
+    label: Lifetime,
+    /// what `?` returns from the function.
+    early: Early,
+}
+
+impl BreakOut {
+    /// Get `break 'label value`.
+    /// It is built instead of parsed, since syn reads `'label ::path` as the label of a block.
+    fn break_out(&self, value: Option<Expr>) -> Expr {
+        Expr::Break(ExprBreak {
+            attrs: Vec::new(),
+            break_token: Default::default(),
+            label: Some(self.label.clone()),
+            expr: value.map(Box::new),
+        })
+    }
+}
+
+impl VisitMut for BreakOut {
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        match expr {
+            Expr::Closure(_) | Expr::Async(_) => {}
+            Expr::Return(ret) => {
+                if let Some(value) = &mut ret.expr {
+                    self.visit_expr_mut(value);
+                }
+                *expr = self.break_out(ret.expr.take().map(|value| *value));
+            }
+            Expr::Try(try_expr) => {
+                self.visit_expr_mut(&mut try_expr.expr);
+                let inner = &try_expr.expr;
+                let (mut early, value): (ExprMatch, Expr) = match self.early {
+                    Early::Err => (
+                        parse_quote! {
+                            match #inner {
+                                ::core::result::Result::Ok(__layla_log_value) => __layla_log_value,
+                                ::core::result::Result::Err(__layla_log_error) => (),
+                            }
+                        },
+                        parse_quote! {
+                            ::core::result::Result::Err(::core::convert::From::from(__layla_log_error))
+                        },
+                    ),
+                    Early::None => (
+                        parse_quote! {
+                            match #inner {
+                                ::core::option::Option::Some(__layla_log_value) => __layla_log_value,
+                                ::core::option::Option::None => (),
+                            }
+                        },
+                        parse_quote!(::core::option::Option::None),
+                    ),
+                    Early::Unknown => return,
+                };
+                *early.arms[1].body = self.break_out(Some(value));
+                *expr = Expr::Match(early);
+            }
+            _ => visit_mut::visit_expr_mut(self, expr),
+        }
+    }
+
+    fn visit_item_mut(&mut self, _item: &mut Item) {}
+}
+
+pub(crate) fn expand(attr: TokenStream, item: TokenStream) -> syn::Result<TokenStream> {
+    let mut level = quote!(::layla_log::LogLevel::Debug);
+    let mut skip: Vec<Ident> = Vec::new();
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("level") {
+            level = parse_level(&meta.value()?.parse()?)?;
+            Ok(())
+        } else if meta.path.is_ident("skip") {
+            meta.parse_nested_meta(|meta| {
+                skip.push(meta.path.require_ident()?.clone());
+                Ok(())
+            })
+        } else {
+            Err(meta.error("unsupported property, expected `level` or `skip`"))
+        }
+    });
+    syn::parse::Parser::parse2(parser, attr)?;
+
+    let ItemFn {
+        attrs,
+        vis,
+        sig,
+        mut block,
+    } = syn::parse2(item)?;
+    let name = sig.ident.to_string();
+
+    let arguments: Vec<&Ident> = sig
+        .inputs
+        .iter()
+        .filter_map(|input| match input {
+            FnArg::Typed(typed) => match &*typed.pat {
+                Pat::Ident(pat) => Some(&pat.ident),
+                _ => None,
+            },
+            FnArg::Receiver(_) => None,
+        })
+        .collect();
+    if let Some(unknown) = skip.iter().find(|ident| !arguments.contains(ident)) {
+        return Err(syn::Error::new_spanned(
+            unknown,
+            format!("`{}` is not an argument of `{}`", unknown, name),
+        ));
+    }
+    let logged: Vec<&Ident> = arguments
+        .into_iter()
+        .filter(|ident| !skip.contains(ident))
+        .collect();
+    let enter = format!(
+        "enter {}({})",
+        name,
+        logged
+            .iter()
+            .map(|ident| format!("{} = {{:?}}", ident))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    let output = match &sig.output {
+        ReturnType::Default => None,
+        ReturnType::Type(_, ty) => Some(&**ty),
+    };
+    let annotation = match output {
+        Some(ty) if has_impl_trait(ty) => quote!(),
+        Some(ty) => quote!(: #ty),
+        None => quote!(: ()),
+    };
+    let exit = match output {
+        Some(ty) if is_result(ty) => quote! {
+            match &__layla_log_result {
+                ::core::result::Result::Ok(value) => (
+                    #level,
+                    format!("exit {} -> Ok({:?}) (took {:?})", #name, value, __layla_log_elapsed),
+                ),
+                ::core::result::Result::Err(error) => (
+                    ::layla_log::LogLevel::Error,
+                    format!("exit {} -> Err({:?}) (took {:?})", #name, error, __layla_log_elapsed),
+                ),
+            }
+        },
+        Some(ty) if !is_unit(ty) => quote! {
+            (
+                #level,
+                format!("exit {} -> {:?} (took {:?})", #name, __layla_log_result, __layla_log_elapsed),
+            )
+        },
+        _ => quote! {
+            (#level, format!("exit {} (took {:?})", #name, __layla_log_elapsed))
+        },
+    };
+
+    let label = Lifetime::new("'__layla_log_body", Span::call_site());
+    let early = match output {
+        Some(ty) if is_result(ty) => Early::Err,
+        Some(ty) if is_option(ty) => Early::None,
+        _ => Early::Unknown,
+    };
+    BreakOut {
+        label: label.clone(),
+        early,
+    }
+    .visit_block_mut(&mut block);
+    let record = if sig.asyncness.is_some() {
+        quote!(::layla_log::__private::record_async)
+    } else {
+        quote!(::layla_log::__private::record)
+    };
+    let wait = sig.asyncness.map(|_| quote!(.await));
+
+    Ok(quote! {
+        #(#attrs)*
+        #vis #sig {
+            let __layla_log_position = ::layla_log::position!();
+            #record(#level, format!(#enter, #(&#logged),*), __layla_log_position)#wait;
+            let __layla_log_started = ::std::time::Instant::now();
+            #[allow(unused_labels)]
+            let __layla_log_result #annotation = #label: #block;
+            let __layla_log_elapsed = __layla_log_started.elapsed();
+            let (__layla_log_level, __layla_log_message) = #exit;
+            #record(__layla_log_level, __layla_log_message, __layla_log_position)#wait;
+            __layla_log_result
+        }
+    })
+}
@@ -0,0 +1,22 @@
+use std::backtrace::{Backtrace, BacktraceStatus};
+use std::error::Error;
+
+/// Format a message with an error, the chain of its sources, and a backtrace when it is captured.
+/// Each cause is a continuation line, so the whole chain is written as the lines of a single log.
+///
+/// The backtrace is captured at the call site, when it is enabled by `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE`.
+pub fn error_chain(message: &str, error: &dyn Error) -> String {
+    let mut lines = vec![format!("{}: {}", message, error)];
+    let mut source = error.source();
+    while let Some(cause) = source {
+        lines.push(format!("caused by: {}", cause));
+        source = cause.source();
+    }
+
+    let backtrace = Backtrace::capture();
+    if backtrace.status() == BacktraceStatus::Captured {
+        lines.push("backtrace:".to_string());
+        lines.extend(backtrace.to_string().lines().map(str::to_string));
+    }
+    lines.join("\n")
+}
@@ -0,0 +1,181 @@
+#[cfg(feature = "encryption")]
+use super::encrypt;
+use super::Setting;
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// The hash before the first log, linked by the header of the first file.
+pub(crate) const GENESIS: &str = "0000000000000000";
+
+/// The beginning of the header of each log file, followed by the final hash of the previous file.
+pub(crate) const HEADER: &str = "#chain ";
+
+/// Get the hash of the line linked to the previous hash, truncated to 16 hex digits.
+pub(crate) fn link(previous: &str, line: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(previous.as_bytes());
+    hasher.update(line.as_bytes());
+    hasher.finalize()[..8]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Get the log files in the directory in the order they were written, which is by the time prefix and then the index.
+/// The time prefix has to sort in the time order, such as the default `%Y-%m-%d`.
+pub(crate) fn log_files(dir: &Path, extension: &str) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let Some((prefix, index)) = name
+            .strip_suffix(extension)
+            .and_then(|stem| stem.rsplit_once('_'))
+        else {
+            continue;
+        };
+        if let Ok(index) = index.parse::<usize>() {
+            files.push((prefix.to_string(), index, path));
+        }
+    }
+    files.sort();
+    Ok(files.into_iter().map(|(_, _, path)| path).collect())
+}
+
+/// Get the first encrypted log file in the directory, which cannot be verified before it is decrypted.
+pub(crate) fn encrypted_file(dir: &Path) -> Option<PathBuf> {
+    log_files(dir, ".log.enc").ok()?.into_iter().next()
+}
+
+/// Get the log files written with the setting in the order they were written,
+/// which are the `.log.enc` files if the encryption key is set.
+pub(crate) fn written_files(setting: &Setting) -> Vec<PathBuf> {
+    let dir = Path::new(&setting.dir_path);
+    #[cfg(feature = "encryption")]
+    if setting.encryption_key.is_some() {
+        return log_files(dir, encrypt::EXTENSION).unwrap_or_default();
+    }
+    log_files(dir, ".log").unwrap_or_default()
+}
+
+/// Read the logs of a file written with the setting, decrypted if the encryption key is set.
+#[cfg_attr(not(feature = "encryption"), allow(unused_variables))]
+pub(crate) fn read_written(file: &Path, setting: &Setting) -> String {
+    #[cfg(feature = "encryption")]
+    if let Some(key) = &setting.encryption_key {
+        return encrypt::decrypt_file(file, key).unwrap_or_default();
+    }
+    std::fs::read_to_string(file).unwrap_or_default()
+}
+
+/// Get the final hash of the chain in the directory, to continue the chain after a restart.
+pub(crate) fn last_hash(setting: &Setting) -> String {
+    for file in written_files(setting).iter().rev() {
+        let content = read_written(file, setting);
+        if let Some(line) = content.lines().last() {
+            if let Some(hash) = line.strip_prefix(HEADER) {
+                return hash.to_string();
+            }
+            if let Some((_, hash)) = line.rsplit_once(" #") {
+                return hash.to_string();
+            }
+        }
+    }
+    GENESIS.to_string()
+}
+
+/// The first broken link found by [`verify`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct BrokenLink {
+    /// the log file with the broken link.
+    pub file: PathBuf,
+    /// the number of the line in the file, starting from 1. (0 for the file itself)
+    pub line: usize,
+    /// why the link is broken.
+    pub reason: String,
+}
+
+impl fmt::Display for BrokenLink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.file.display(), self.line, self.reason)
+    }
+}
+
+impl std::error::Error for BrokenLink {}
+
+/// Verify the hash chain of the log files in the directory, written with `hash_chain` enabled.
+/// Get the number of the verified logs, or the first broken link.
+/// A directory without any log file, or with an encrypted one, is reported as broken, so that it does not pass unchecked.
+/// The header of the first file is not checked, since the older files may have been removed.
+pub fn verify(dir: impl AsRef<Path>) -> Result<usize, BrokenLink> {
+    let dir = dir.as_ref();
+    let files = log_files(dir, ".log").map_err(|error| BrokenLink {
+        file: dir.to_path_buf(),
+        line: 0,
+        reason: format!("cannot read the directory: {}", error),
+    })?;
+    if let Some(file) = encrypted_file(dir) {
+        return Err(BrokenLink {
+            file,
+            line: 0,
+            reason: "the file is encrypted, decrypt it before verifying".to_string(),
+        });
+    }
+    if files.is_empty() {
+        return Err(BrokenLink {
+            file: dir.to_path_buf(),
+            line: 0,
+            reason: "no log file is found".to_string(),
+        });
+    }
+
+    let mut previous: Option<String> = None;
+    let mut count = 0;
+    for file in files {
+        let broken = |line: usize, reason: String| BrokenLink {
+            file: file.clone(),
+            line,
+            reason,
+        };
+        let content = std::fs::read_to_string(&file)
+            .map_err(|error| broken(0, format!("cannot read the file: {}", error)))?;
+        let mut lines = content.lines();
+        let Some(header) = lines.next() else {
+            continue;
+        };
+        let Some(linked) = header.strip_prefix(HEADER) else {
+            return Err(broken(1, "missing the chain header".to_string()));
+        };
+        if let Some(previous) = &previous {
+            if linked != previous {
+                return Err(broken(
+                    1,
+                    format!(
+                        "the header links to {}, but the previous file ends with {}",
+                        linked, previous
+                    ),
+                ));
+            }
+        }
+
+        let mut hash = linked.to_string();
+        for (index, line) in lines.enumerate() {
+            let Some((content, recorded)) = line.rsplit_once(" #") else {
+                return Err(broken(index + 2, "missing the hash".to_string()));
+            };
+            hash = link(&hash, content);
+            if recorded != hash {
+                return Err(broken(
+                    index + 2,
+                    format!("the hash is {}, but {} is expected", recorded, hash),
+                ));
+            }
+            count += 1;
+        }
+        previous = Some(hash);
+    }
+    Ok(count)
+}
@@ -0,0 +1,108 @@
+use super::Setting;
+use lazy_static::lazy_static;
+
+lazy_static! {
+    /// The name of the host, which does not change during the process.
+    static ref HOSTNAME: String = hostname::get()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|_| "unknown".to_string());
+}
+
+/// The information of where a log is emitted from, captured at the call site.
+/// Each field is only captured when it is enabled in [`Setting`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct Context {
+    /// the id of the process.
+    process_id: Option<u32>,
+    /// the name of the host.
+    hostname: Option<String>,
+    /// the name of the thread.
+    thread_name: Option<String>,
+    /// the id of the thread.
+    thread_id: Option<String>,
+    /// the id of the tokio task.
+    task_id: Option<String>,
+}
+
+impl Context {
+    /// Capture the enabled fields from the current thread (and task).
+    pub fn capture(setting: &Setting) -> Self {
+        let thread = std::thread::current();
+        Self {
+            process_id: setting.record_process_id.then(std::process::id),
+            hostname: setting.record_hostname.then(|| HOSTNAME.clone()),
+            thread_name: setting.record_thread_name.then(|| thread_name(&thread)),
+            thread_id: setting.record_thread_id.then(|| thread_id(&thread)),
+            task_id: setting.record_task_id.then(task_id).flatten(),
+        }
+    }
+
+    /// Capture all the fields from the current thread (and task) before the setting is known,
+    /// for a detached log which may be recorded later by another task.
+    #[cfg(feature = "async")]
+    pub fn capture_all() -> Self {
+        let thread = std::thread::current();
+        Self {
+            process_id: Some(std::process::id()),
+            hostname: Some(HOSTNAME.clone()),
+            thread_name: Some(thread_name(&thread)),
+            thread_id: Some(thread_id(&thread)),
+            task_id: task_id(),
+        }
+    }
+
+    /// Keep only the fields enabled in [`Setting`].
+    #[cfg(feature = "async")]
+    pub fn only(self, setting: &Setting) -> Self {
+        Self {
+            process_id: self.process_id.filter(|_| setting.record_process_id),
+            hostname: self.hostname.filter(|_| setting.record_hostname),
+            thread_name: self.thread_name.filter(|_| setting.record_thread_name),
+            thread_id: self.thread_id.filter(|_| setting.record_thread_id),
+            task_id: self.task_id.filter(|_| setting.record_task_id),
+        }
+    }
+
+    /// Check whether no field is captured.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl std::fmt::Display for Context {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let fields = [
+            ("pid", self.process_id.map(|id| id.to_string())),
+            ("host", self.hostname.clone()),
+            ("thread", self.thread_name.clone()),
+            ("thread_id", self.thread_id.clone()),
+            ("task", self.task_id.clone()),
+        ];
+        let fields: Vec<String> = fields
+            .into_iter()
+            .filter_map(|(key, value)| value.map(|value| format!("{}={}", key, value)))
+            .collect();
+        write!(f, "{}", fields.join(" "))
+    }
+}
+
+fn thread_name(thread: &std::thread::Thread) -> String {
+    thread.name().unwrap_or("<unnamed>").to_string()
+}
+
+fn thread_id(thread: &std::thread::Thread) -> String {
+    let id = format!("{:?}", thread.id());
+    id.trim_start_matches("ThreadId(")
+        .trim_end_matches(')')
+        .to_string()
+}
+
+#[cfg(feature = "async")]
+fn task_id() -> Option<String> {
+    Some(tokio::task::try_id().map_or_else(|| "none".to_string(), |id| id.to_string()))
+}
+
+#[cfg(not(feature = "async"))]
+fn task_id() -> Option<String> {
+    None
+}
@@ -0,0 +1,68 @@
+use super::msg::LogMessage;
+use std::time::{Duration, Instant};
+
+/// A stage that suppresses the consecutive repeated logs,
+/// and summarizes them with a single log when the run of repeats ends.
+#[derive(Debug, Default)]
+pub(crate) struct Dedup {
+    /// the first log of the current run, which has been written.
+    first: Option<LogMessage>,
+    /// the last suppressed repeat of the current run.
+    last: Option<LogMessage>,
+    /// the number of the suppressed repeats.
+    repeated: usize,
+    /// when the current run began.
+    since: Option<Instant>,
+}
+
+impl Dedup {
+    /// Check the log against the current run.
+    /// Returns the summary of the ended run (if any), and whether the log should be written.
+    pub(crate) fn check(
+        &mut self,
+        msg: &LogMessage,
+        window: Duration,
+    ) -> (Option<LogMessage>, bool) {
+        let in_window = self.since.is_some_and(|since| since.elapsed() <= window);
+        if in_window
+            && self
+                .first
+                .as_ref()
+                .is_some_and(|first| first.is_repeat_of(msg))
+        {
+            self.repeated += 1;
+            self.last = Some(msg.clone());
+            return (None, false);
+        }
+
+        let summary = self.flush();
+        self.first = Some(msg.clone());
+        self.since = Some(Instant::now());
+        (summary, true)
+    }
+
+    /// End the current run if the window has expired, and get the summary of it if any log has been suppressed.
+    pub(crate) fn expire(&mut self, window: Duration) -> Option<LogMessage> {
+        if self.since.is_some_and(|since| since.elapsed() > window) {
+            self.flush()
+        } else {
+            None
+        }
+    }
+
+    /// End the current run, and get the summary of it if any log has been suppressed.
+    pub(crate) fn flush(&mut self) -> Option<LogMessage> {
+        let repeated = std::mem::take(&mut self.repeated);
+        let last = self.last.take()?;
+        let first = self.first.take()?;
+        self.since = None;
+
+        let span = last.time.since(&first.time);
+        Some(last.with_message(format!(
+            "last message repeated {} times in {}.{:03}s",
+            repeated,
+            span.num_seconds(),
+            span.subsec_millis()
+        )))
+    }
+}
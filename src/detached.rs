@@ -0,0 +1,33 @@
+use super::{context::Context, Location, LogLevel};
+use std::sync::{Mutex, PoisonError};
+
+/// A log recorded without awaiting the lock of the logger, such as in [`Drop`].
+#[derive(Debug)]
+pub(crate) struct Detached {
+    /// the level of the log.
+    pub(crate) level: LogLevel,
+    /// the message of the log.
+    pub(crate) message: String,
+    /// where the log is recorded.
+    pub(crate) position: Location,
+    /// the path of the spans where the log is recorded.
+    pub(crate) span: Option<String>,
+    /// the context captured where the log is recorded, filtered by the setting when it is written.
+    pub(crate) context: Context,
+}
+
+/// The detached logs not written yet, in the order they were recorded.
+static PENDING: Mutex<Vec<Detached>> = Mutex::new(Vec::new());
+
+/// Queue the log, until the logger writes the pending logs.
+pub(crate) fn push(log: Detached) {
+    PENDING
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .push(log);
+}
+
+/// Take the pending logs to write.
+pub(crate) fn take() -> Vec<Detached> {
+    std::mem::take(&mut *PENDING.lock().unwrap_or_else(PoisonError::into_inner))
+}
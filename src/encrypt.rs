@@ -0,0 +1,210 @@
+use super::SecretKey;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce};
+use std::io::{self, Read};
+use std::path::Path;
+
+/// The beginning of each encrypted log file, followed by the random id of the file.
+const MAGIC: &[u8; 8] = b"LAYLAENC";
+
+/// The length of the random id of each encrypted log file.
+const FILE_ID: usize = 16;
+
+/// The length of the nonce of each chunk.
+const NONCE: usize = 12;
+
+/// The extension of the encrypted log files.
+pub(crate) const EXTENSION: &str = ".log.enc";
+
+/// Get the associated data of the chunk, which binds the chunk to its file and position.
+fn associated_data(file_id: &[u8], index: u64) -> Vec<u8> {
+    let mut aad = file_id.to_vec();
+    aad.extend_from_slice(&index.to_be_bytes());
+    aad
+}
+
+/// Create the cipher of the key, which has to be 32 bytes.
+pub(crate) fn cipher(key: &SecretKey) -> Result<ChaCha20Poly1305, String> {
+    ChaCha20Poly1305::new_from_slice(key.as_bytes()).map_err(|_| {
+        format!(
+            "The encryption key has {} bytes, but 32 bytes are expected",
+            key.as_bytes().len()
+        )
+    })
+}
+
+/// The state of encrypting the log files.
+/// Each write is encrypted as a chunk: the length (4 bytes, big endian), the nonce and the ciphertext,
+/// so that a crash only loses the chunk being written.
+pub(crate) struct Encryptor {
+    /// the cipher of the key.
+    cipher: ChaCha20Poly1305,
+    /// the path of the file being encrypted.
+    path: String,
+    /// the random id of the file.
+    file_id: [u8; FILE_ID],
+    /// the index of the next chunk.
+    index: u64,
+}
+
+impl Encryptor {
+    /// Create the encryptor with the key, which has to be 32 bytes.
+    pub(crate) fn new(key: &SecretKey) -> Result<Self, String> {
+        Ok(Self {
+            cipher: cipher(key)?,
+            path: String::new(),
+            file_id: [0; FILE_ID],
+            index: 0,
+        })
+    }
+
+    /// Forget the file being encrypted, so that the header is written again even if the next file has the same path.
+    pub(crate) fn reset(&mut self) {
+        self.path.clear();
+        self.index = 0;
+    }
+
+    /// Encrypt the text as the next chunk of the file.
+    /// The header is written before the first chunk of each file, with a new random id.
+    pub(crate) fn encrypt(&mut self, path: String, text: &str) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        if self.path != path {
+            self.path = path;
+            OsRng.fill_bytes(&mut self.file_id);
+            self.index = 0;
+            bytes.extend_from_slice(MAGIC);
+            bytes.extend_from_slice(&self.file_id);
+        }
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: text.as_bytes(),
+                    aad: &associated_data(&self.file_id, self.index),
+                },
+            )
+            .expect("Cannot encrypt the log.");
+        bytes.extend_from_slice(&(ciphertext.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(&nonce);
+        bytes.extend_from_slice(&ciphertext);
+        self.index += 1;
+        bytes
+    }
+}
+
+impl std::fmt::Debug for Encryptor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Encryptor")
+            .field("path", &self.path)
+            .field("index", &self.index)
+            .finish()
+    }
+}
+
+/// A reader of the decrypted logs of an encrypted log file.
+/// An incomplete chunk at the end, left by a crash, is ignored. A chunk that fails the authentication is an error.
+pub struct DecryptReader<R: Read> {
+    /// the encrypted log file.
+    inner: R,
+    /// the cipher of the key.
+    cipher: ChaCha20Poly1305,
+    /// the random id of the file.
+    file_id: [u8; FILE_ID],
+    /// the index of the next chunk.
+    index: u64,
+    /// the decrypted chunk not read yet.
+    buffer: Vec<u8>,
+    /// the position of the unread part of the buffer.
+    position: usize,
+}
+
+impl<R: Read> DecryptReader<R> {
+    /// Create a reader of the encrypted log file with the key, and check the header of the file.
+    pub fn new(mut inner: R, key: &SecretKey) -> io::Result<Self> {
+        let cipher =
+            cipher(key).map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+        let mut magic = [0; MAGIC.len()];
+        inner.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "not an encrypted log file",
+            ));
+        }
+        let mut file_id = [0; FILE_ID];
+        inner.read_exact(&mut file_id)?;
+        Ok(Self {
+            inner,
+            cipher,
+            file_id,
+            index: 0,
+            buffer: Vec::new(),
+            position: 0,
+        })
+    }
+
+    /// Read exactly the length of bytes, or get `None` at the end of the file or a chunk cut by a crash.
+    fn read_part(&mut self, length: usize) -> io::Result<Option<Vec<u8>>> {
+        let mut bytes = Vec::new();
+        (&mut self.inner)
+            .take(length as u64)
+            .read_to_end(&mut bytes)?;
+        Ok((bytes.len() == length).then_some(bytes))
+    }
+
+    /// Decrypt the next chunk into the buffer, and get whether there is one.
+    fn next_chunk(&mut self) -> io::Result<bool> {
+        let Some(length) = self.read_part(4)? else {
+            return Ok(false);
+        };
+        let length = u32::from_be_bytes(length.try_into().expect("4 bytes")) as usize;
+        let Some(nonce) = self.read_part(NONCE)? else {
+            return Ok(false);
+        };
+        let Some(ciphertext) = self.read_part(length)? else {
+            return Ok(false);
+        };
+        self.buffer = self
+            .cipher
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: &associated_data(&self.file_id, self.index),
+                },
+            )
+            .map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("the chunk {} of the log file is altered", self.index),
+                )
+            })?;
+        self.position = 0;
+        self.index += 1;
+        Ok(true)
+    }
+}
+
+impl<R: Read> Read for DecryptReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.position >= self.buffer.len() {
+            if !self.next_chunk()? {
+                return Ok(0);
+            }
+        }
+        let length = buf.len().min(self.buffer.len() - self.position);
+        buf[..length].copy_from_slice(&self.buffer[self.position..self.position + length]);
+        self.position += length;
+        Ok(length)
+    }
+}
+
+/// Read the decrypted logs of an encrypted log file.
+pub fn decrypt_file(path: impl AsRef<Path>, key: &SecretKey) -> io::Result<String> {
+    let mut logs = String::new();
+    DecryptReader::new(std::fs::File::open(path)?, key)?.read_to_string(&mut logs)?;
+    Ok(logs)
+}
@@ -0,0 +1,80 @@
+use super::{record_detached, Location, LogLevel};
+use std::fmt::Display;
+
+/// Extension methods for [`Result`] that log on the error path and pass the value through unchanged.
+/// The logs are recorded with the caller's location.
+pub trait LogResultExt<T> {
+    /// Log the error with the context at the level, if it is an error.
+    fn log_err(self, level: LogLevel, context: &str) -> Self;
+
+    /// Get the value, or log the error as a warning and get the default value.
+    fn unwrap_or_log(self, default: T) -> T;
+}
+
+impl<T, E: Display> LogResultExt<T> for Result<T, E> {
+    #[track_caller]
+    fn log_err(self, level: LogLevel, context: &str) -> Self {
+        if let Err(error) = &self {
+            record_detached(level, format!("{}: {}", context, error), Location::caller());
+        }
+        self
+    }
+
+    #[track_caller]
+    fn unwrap_or_log(self, default: T) -> T {
+        match self {
+            Ok(value) => value,
+            Err(error) => {
+                record_detached(
+                    LogLevel::Warn,
+                    format!("{}, the default value is used", error),
+                    Location::caller(),
+                );
+                default
+            }
+        }
+    }
+}
+
+/// Extension methods for [`Option`] that log when there is no value and pass the value through unchanged.
+/// The logs are recorded with the caller's location.
+pub trait LogOptionExt<T> {
+    /// Log the context at the level, if there is no value.
+    fn log_none(self, level: LogLevel, context: &str) -> Self;
+
+    /// Log the context as a warning, if there is no value.
+    fn log_warn_none(self, context: &str) -> Self;
+
+    /// Get the value, or log a warning and get the default value.
+    fn unwrap_or_log(self, default: T) -> T;
+}
+
+impl<T> LogOptionExt<T> for Option<T> {
+    #[track_caller]
+    fn log_none(self, level: LogLevel, context: &str) -> Self {
+        if self.is_none() {
+            record_detached(level, context.to_string(), Location::caller());
+        }
+        self
+    }
+
+    #[track_caller]
+    fn log_warn_none(self, context: &str) -> Self {
+        self.log_none(LogLevel::Warn, context)
+    }
+
+    #[track_caller]
+    fn unwrap_or_log(self, default: T) -> T {
+        match self {
+            Some(value) => value,
+            None => {
+                record_detached(
+                    LogLevel::Warn,
+                    "no value, the default value is used".to_string(),
+                    Location::caller(),
+                );
+                default
+            }
+        }
+    }
+}
@@ -0,0 +1,23 @@
+/// A type that can be logged as structured `key=value` fields, such as `info!(order; "created")`.
+///
+/// It can be derived with `#[derive(LogFields)]` by the `macros` feature, where the fields can be
+/// marked with `#[log(skip)]`, `#[log(redact)]` or `#[log(rename = "...")]`.
+pub trait LogFields {
+    /// Get the fields as the pairs of key and value.
+    fn log_fields(&self) -> Vec<(&'static str, String)>;
+
+    /// Format the fields as `key=value` pairs separated by spaces.
+    fn format_fields(&self) -> String {
+        self.log_fields()
+            .into_iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl<T: LogFields + ?Sized> LogFields for &T {
+    fn log_fields(&self) -> Vec<(&'static str, String)> {
+        (**self).log_fields()
+    }
+}
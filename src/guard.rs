@@ -0,0 +1,23 @@
+use super::shutdown_detached;
+
+/// A guard that shuts down the logger when it is dropped, returned by `init_with_guard`.
+/// Keep it alive until the end of `main`, so that the logs are flushed and synced before the process exits.
+/// It is not dropped by [`std::process::exit`], so call `shutdown` before exiting in that way.
+#[must_use = "the logger is shut down when the guard is dropped"]
+#[derive(Debug)]
+pub struct LoggerGuard {
+    _private: (),
+}
+
+impl LoggerGuard {
+    /// Create a guard of the static logger.
+    pub(crate) fn new() -> Self {
+        Self { _private: () }
+    }
+}
+
+impl Drop for LoggerGuard {
+    fn drop(&mut self) {
+        shutdown_detached();
+    }
+}
@@ -0,0 +1,189 @@
+use lazy_static::lazy_static;
+use std::cmp::Ordering;
+use std::sync::atomic::{self, AtomicUsize};
+use std::sync::RwLock;
+
+lazy_static! {
+    /// The custom levels registered by [`LogLevel::register`], used when parsing a level from its name.
+    static ref CUSTOM_LEVELS: RwLock<Vec<CustomLevel>> = RwLock::new(Vec::new());
+}
+
+/// The width of the level column, which is the longest name of the built-in and registered levels.
+static WIDTH: AtomicUsize = AtomicUsize::new(5);
+
+/// A user-defined level, such as `AUDIT` or `SECURITY`.
+///
+/// The severity uses the same scale as [`LogLevel::get_level`], so a level between
+/// [`LogLevel::Info`] (200) and [`LogLevel::Warn`] (300) can be defined with a severity of 250.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CustomLevel {
+    /// the name shown in the log.
+    name: &'static str,
+    /// the severity compared with the record levels in [`Setting`](crate::Setting).
+    severity: usize,
+    /// whether the log is written to the file whatever the file record level is.
+    always_record: bool,
+}
+
+impl CustomLevel {
+    /// Create a custom level with a name and a severity.
+    pub const fn new(name: &'static str, severity: usize) -> Self {
+        Self {
+            name,
+            severity,
+            always_record: false,
+        }
+    }
+
+    /// Make the logs of this level always written to the file.
+    pub const fn always_record(mut self) -> Self {
+        self.always_record = true;
+        self
+    }
+
+    /// Get the name of the level.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Get the severity of the level.
+    pub fn severity(&self) -> usize {
+        self.severity
+    }
+}
+
+/// Enumeration of log levels.
+/// This defines the emergency of the log.
+/// (the levels are ordered by [`LogLevel::get_level`], and the order is used to decide write to the log file or not.)
+/// [`LogLevel::Off`] is the highest level, it is only used as a threshold to turn the output off.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Fatal,
+    Off,
+    Custom(CustomLevel),
+}
+
+impl std::fmt::Display for LogLevel {
+    /// The name is padded to the longest name of the built-in and registered levels, so the columns stay aligned.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let width = WIDTH.load(atomic::Ordering::Relaxed);
+        write!(f, "{:<width$}", self.name())
+    }
+}
+
+impl std::str::FromStr for LogLevel {
+    type Err = ParseLevelError;
+
+    /// Parse the level from its name, case-insensitively.
+    /// Surrounding whitespace is ignored, so the padded [`Display`](std::fmt::Display) output can be parsed back.
+    /// Custom levels can be parsed once they are registered by [`LogLevel::register`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim();
+        let custom = CUSTOM_LEVELS
+            .read()
+            .expect("Cannot read the custom levels.");
+        [
+            LogLevel::Trace,
+            LogLevel::Debug,
+            LogLevel::Info,
+            LogLevel::Warn,
+            LogLevel::Error,
+            LogLevel::Fatal,
+            LogLevel::Off,
+        ]
+        .into_iter()
+        .chain(custom.iter().copied().map(LogLevel::Custom))
+        .find(|level| level.name().eq_ignore_ascii_case(name))
+        .ok_or_else(|| ParseLevelError(name.to_string()))
+    }
+}
+
+impl PartialOrd for LogLevel {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for LogLevel {
+    /// Levels are compared by their severity first, the name only breaks the tie of different levels.
+    /// The record levels in [`Setting`](crate::Setting) are compared by [`LogLevel::is_at_least`] instead, which ignores the names.
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.get_level()
+            .cmp(&other.get_level())
+            .then_with(|| self.name().cmp(other.name()))
+            .then_with(|| self.is_always_recorded().cmp(&other.is_always_recorded()))
+    }
+}
+
+impl LogLevel {
+    /// Get the severity of the level.
+    pub fn get_level(&self) -> usize {
+        match self {
+            LogLevel::Trace => 0,
+            LogLevel::Debug => 100,
+            LogLevel::Info => 200,
+            LogLevel::Warn => 300,
+            LogLevel::Error => 400,
+            LogLevel::Fatal => 500,
+            LogLevel::Off => usize::MAX,
+            LogLevel::Custom(level) => level.severity,
+        }
+    }
+
+    /// Check whether the level is at least as severe as the threshold.
+    /// Only the severities are compared, so a level with the same severity as the threshold always passes.
+    pub fn is_at_least(&self, threshold: LogLevel) -> bool {
+        self.get_level() >= threshold.get_level()
+    }
+
+    /// Get the name of the level, without padding.
+    pub fn name(&self) -> &'static str {
+        match self {
+            LogLevel::Trace => "TRACE",
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Info => "INFO",
+            LogLevel::Warn => "WARN",
+            LogLevel::Error => "ERROR",
+            LogLevel::Fatal => "FATAL",
+            LogLevel::Off => "OFF",
+            LogLevel::Custom(level) => level.name,
+        }
+    }
+
+    /// Check whether the logs of this level are written to the file whatever the file record level is.
+    pub fn is_always_recorded(&self) -> bool {
+        matches!(self, LogLevel::Custom(level) if level.always_record)
+    }
+
+    /// Register a custom level, so it can be parsed from its name.
+    /// A registered level with the same name is replaced.
+    /// The level column is widened to the name, so register the levels before logging to keep the columns aligned.
+    pub fn register(level: CustomLevel) -> LogLevel {
+        WIDTH.fetch_max(level.name.len(), atomic::Ordering::Relaxed);
+        let mut custom = CUSTOM_LEVELS
+            .write()
+            .expect("Cannot write the custom levels.");
+        custom.retain(|registered| !registered.name.eq_ignore_ascii_case(level.name));
+        custom.push(level);
+        LogLevel::Custom(level)
+    }
+}
+
+/// The error returned when a string cannot be parsed into a [`LogLevel`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseLevelError(String);
+
+impl std::fmt::Display for ParseLevelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown log level: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseLevelError {}
+
+unsafe impl Send for LogLevel {}
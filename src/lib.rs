@@ -2,13 +2,86 @@
 //! It can be used to write logs in a program. The logs can be written to a dictionary.
 //! The log level can be set to different levels (Error, Warn, Debug, Info and Trace).
 
+mod cause;
+#[cfg(feature = "integrity")]
+mod chain;
+mod context;
+mod dedup;
+#[cfg(feature = "async")]
+mod detached;
+#[cfg(feature = "encryption")]
+mod encrypt;
+mod ext;
+mod fields;
+mod guard;
+mod key;
+mod level;
+mod limit;
+mod location;
 mod logger;
 mod msg;
+#[cfg(feature = "redaction")]
+mod redact;
+mod sample;
 mod setting;
+#[cfg(feature = "integrity")]
+mod signing;
+mod span;
 mod time;
-
+mod timer;
+
+pub use cause::error_chain;
+#[cfg(feature = "integrity")]
+pub use chain::{verify, BrokenLink};
+#[cfg(feature = "encryption")]
+pub use encrypt::{decrypt_file, DecryptReader};
+pub use ext::{LogOptionExt, LogResultExt};
+pub use fields::LogFields;
+pub use guard::LoggerGuard;
+pub use key::SecretKey;
+pub use level::{CustomLevel, LogLevel, ParseLevelError};
+pub use limit::RateLimit;
+pub use location::{Location, PositionFormat};
 pub use logger::*;
-pub use setting::Setting;
+#[cfg(feature = "redaction")]
+pub use redact::{RedactRule, RedactTarget, Redaction};
+pub use sample::{Sample, SampleRule};
+pub use setting::{Setting, SyncPolicy};
+#[cfg(feature = "integrity")]
+pub use signing::{verify_signatures, SignatureIssue, SignatureReport};
+pub use span::Span;
+pub use time::{Precision, TimeFormat, TimeFormatError, TimeZone, TimeZoneError};
+pub use timer::LogTimer;
+
+#[cfg(feature = "macros")]
+pub use layla_log_macros::{logged, LogFields};
+
+/// Functions used by the code generated by the procedural macros, not a part of the public API.
+#[doc(hidden)]
+pub mod __private {
+    use super::{record_detached, Location, LogLevel};
+
+    /// Record a log from a synchronous function.
+    pub fn record(level: LogLevel, message: String, position: Location) {
+        record_detached(level, message, position);
+    }
+
+    /// Record a log from an asynchronous function.
+    #[cfg(feature = "async")]
+    pub async fn record_async(level: LogLevel, message: String, position: Location) {
+        super::LOGGER
+            .lock()
+            .await
+            .record(level, &message, position)
+            .await;
+    }
+
+    /// Record a log from an asynchronous function.
+    #[cfg(not(feature = "async"))]
+    pub async fn record_async(level: LogLevel, message: String, position: Location) {
+        record_detached(level, message, position);
+    }
+}
 
 #[cfg(feature = "async")]
 pub use async_log::*;
@@ -16,10 +89,10 @@ pub use async_log::*;
 pub use log::*;
 
 use lazy_static::lazy_static;
+use std::sync::atomic::{AtomicBool, Ordering};
 #[cfg(not(feature = "async"))]
 use std::sync::Mutex;
-#[cfg(feature = "async")]
-use tokio;
+use std::time::Duration;
 #[cfg(feature = "async")]
 use tokio::sync::Mutex;
 
@@ -29,6 +102,9 @@ lazy_static! {
     pub static ref LOGGER: Mutex<Logger> = Mutex::new(Logger::new());
 }
 
+/// Whether the timer doing the periodic work of the logger has been started.
+static TIMER: AtomicBool = AtomicBool::new(false);
+
 /// A macro that returns the name of the function it is called in.
 #[macro_export]
 macro_rules! func {
@@ -46,18 +122,192 @@ macro_rules! func {
     }};
 }
 
-/// A macro that returns the current position in the code.
+/// A macro that returns the full path of the function it is called in.
+/// The trailing closure segments are stripped.
 #[macro_export]
-macro_rules! position {
+macro_rules! func_path {
     () => {{
-        let function = $crate::func!();
-        let file = file!();
-        let line = line!();
-        let column = column!();
-        format!("{} @ {}:{}:{}", function, file, line, column)
+        fn f() {}
+        fn type_name_of<T>(_: T) -> &'static str {
+            std::any::type_name::<T>()
+        }
+        let mut name = type_name_of(f).strip_suffix("::f").unwrap();
+        while let Some(outer) = name.strip_suffix("::{{closure}}") {
+            name = outer;
+        }
+        name
+    }};
+}
+
+/// A macro that returns the current position in the code, as a [`Location`].
+#[macro_export]
+macro_rules! position {
+    () => {
+        $crate::Location {
+            module_path: module_path!(),
+            function: $crate::func_path!(),
+            file: file!(),
+            line: line!(),
+            column: column!(),
+        }
+    };
+}
+
+/// A macro to log only one of every `n` calls from this call site, by [`log!`].
+/// The count is kept per call site, and the message is not formatted for the skipped calls.
+#[macro_export]
+macro_rules! log_every_n {
+    ($level:expr, $n:expr, $($arg:tt)*) => {{
+        static COUNT: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+        let n: usize = $n;
+        if COUNT
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+            .is_multiple_of(n.max(1))
+        {
+            $crate::log!($level, $($arg)*);
+        }
     }};
 }
 
+/// A macro to log only the first call from this call site, by [`log!`].
+#[macro_export]
+macro_rules! log_once {
+    ($level:expr, $($arg:tt)*) => {{
+        static DONE: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+        if !DONE.swap(true, std::sync::atomic::Ordering::Relaxed) {
+            $crate::log!($level, $($arg)*);
+        }
+    }};
+}
+
+/// A macro to measure a block, log the elapsed time by [`log!`], and evaluate to the value of the block.
+/// With a slow threshold (`threshold => level`), the log is escalated to the level when the block is slower than it.
+#[macro_export]
+macro_rules! time_block {
+    ($level:expr, $name:expr, $threshold:expr => $slow_level:expr, $body:block) => {{
+        let started = std::time::Instant::now();
+        let value = $body;
+        let elapsed = started.elapsed();
+        let level = if elapsed > $threshold {
+            $slow_level
+        } else {
+            $level
+        };
+        $crate::log!(level, "{} took {:?}", $name, elapsed);
+        value
+    }};
+    ($level:expr, $name:expr, $body:block) => {{
+        let started = std::time::Instant::now();
+        let value = $body;
+        $crate::log!($level, "{} took {:?}", $name, started.elapsed());
+        value
+    }};
+}
+
+/// A macro to create a [`LogTimer`] at the current position, which logs the elapsed time when dropped.
+#[macro_export]
+macro_rules! timer {
+    ($level:expr, $name:expr) => {
+        $crate::LogTimer::new($level, $name, $crate::position!())
+    };
+}
+
+/// Macro to log a trace message once every `n` calls from this call site, by [`log_every_n!`].
+#[macro_export]
+macro_rules! trace_every_n {
+    ($n:expr, $($arg:tt)*) => {
+        $crate::log_every_n!($crate::LogLevel::Trace, $n, $($arg)*)
+    };
+}
+
+/// Macro to log a trace message only once from this call site, by [`log_once!`].
+#[macro_export]
+macro_rules! trace_once {
+    ($($arg:tt)*) => {
+        $crate::log_once!($crate::LogLevel::Trace, $($arg)*)
+    };
+}
+
+/// Macro to log a debug message once every `n` calls from this call site, by [`log_every_n!`].
+#[macro_export]
+macro_rules! debug_every_n {
+    ($n:expr, $($arg:tt)*) => {
+        $crate::log_every_n!($crate::LogLevel::Debug, $n, $($arg)*)
+    };
+}
+
+/// Macro to log a debug message only once from this call site, by [`log_once!`].
+#[macro_export]
+macro_rules! debug_once {
+    ($($arg:tt)*) => {
+        $crate::log_once!($crate::LogLevel::Debug, $($arg)*)
+    };
+}
+
+/// Macro to log an info message once every `n` calls from this call site, by [`log_every_n!`].
+#[macro_export]
+macro_rules! info_every_n {
+    ($n:expr, $($arg:tt)*) => {
+        $crate::log_every_n!($crate::LogLevel::Info, $n, $($arg)*)
+    };
+}
+
+/// Macro to log an info message only once from this call site, by [`log_once!`].
+#[macro_export]
+macro_rules! info_once {
+    ($($arg:tt)*) => {
+        $crate::log_once!($crate::LogLevel::Info, $($arg)*)
+    };
+}
+
+/// Macro to log a warning message once every `n` calls from this call site, by [`log_every_n!`].
+#[macro_export]
+macro_rules! warn_every_n {
+    ($n:expr, $($arg:tt)*) => {
+        $crate::log_every_n!($crate::LogLevel::Warn, $n, $($arg)*)
+    };
+}
+
+/// Macro to log a warning message only once from this call site, by [`log_once!`].
+#[macro_export]
+macro_rules! warn_once {
+    ($($arg:tt)*) => {
+        $crate::log_once!($crate::LogLevel::Warn, $($arg)*)
+    };
+}
+
+/// Macro to log an error message once every `n` calls from this call site, by [`log_every_n!`].
+#[macro_export]
+macro_rules! error_every_n {
+    ($n:expr, $($arg:tt)*) => {
+        $crate::log_every_n!($crate::LogLevel::Error, $n, $($arg)*)
+    };
+}
+
+/// Macro to log an error message only once from this call site, by [`log_once!`].
+#[macro_export]
+macro_rules! error_once {
+    ($($arg:tt)*) => {
+        $crate::log_once!($crate::LogLevel::Error, $($arg)*)
+    };
+}
+
+/// Macro to log a fatal message once every `n` calls from this call site, by [`log_every_n!`].
+#[macro_export]
+macro_rules! fatal_every_n {
+    ($n:expr, $($arg:tt)*) => {
+        $crate::log_every_n!($crate::LogLevel::Fatal, $n, $($arg)*)
+    };
+}
+
+/// Macro to log a fatal message only once from this call site, by [`log_once!`].
+#[macro_export]
+macro_rules! fatal_once {
+    ($($arg:tt)*) => {
+        $crate::log_once!($crate::LogLevel::Fatal, $($arg)*)
+    };
+}
+
 #[cfg(feature = "async")]
 mod async_log {
     use super::*;
@@ -70,6 +320,23 @@ mod async_log {
         // Call the `init` method on the locked logger, passing in the `setting` parameter.
         // This initializes the logger with the provided settings.
         logger.init(setting).await;
+        if let Some(period) = logger.tick_period() {
+            start_timer(period);
+        }
+    }
+
+    /// Initialize the static logger with customized setting,
+    /// and get a guard which shuts down the logger by [`shutdown`] when it is dropped.
+    pub async fn init_with_guard(setting: Setting) -> LoggerGuard {
+        init(setting).await;
+        LoggerGuard::new()
+    }
+
+    /// Shut down the logger: write the summary of the repeated logs, the shutdown log if `shutdown_record` is set,
+    /// and the signature of the logs not signed yet, then flush and sync the current log file.
+    /// Call it before [`std::process::exit`], which does not drop the [`LoggerGuard`].
+    pub async fn shutdown() {
+        LOGGER.lock().await.shutdown().await;
     }
 
     /// Define a public asynchronous function named `clean_log`
@@ -82,64 +349,135 @@ mod async_log {
         writer.clear_dir().await;
     }
 
+    /// Start the timer doing the periodic work of the logger, once per process.
+    fn start_timer(period: Duration) {
+        if TIMER.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(period).await;
+                LOGGER.lock().await.tick().await;
+            }
+        });
+    }
+
+    /// Macro to log fatal message.
+    /// Log the message by [`log!`] with [`LogLevel::Fatal`].
+    #[macro_export]
+    macro_rules! fatal {
+        ($($arg:tt)*) => {
+            $crate::log!($crate::LogLevel::Fatal, $($arg)*)
+        };
+    }
+
     /// Macro to log error message.
-    /// First lock the logger in static, then log the message.
+    /// Log the message by [`log!`] with [`LogLevel::Error`].
     #[macro_export]
     macro_rules! error {
         ($($arg:tt)*) => {
-            let position = $crate::position!().to_string();
-            $crate::LOGGER.lock().await.error(format!($($arg)*).as_str(), position).await;
+            $crate::log!($crate::LogLevel::Error, $($arg)*)
         };
     }
 
     /// Macro to log warning message.
-    /// First lock the logger in static, then log the message.
+    /// Log the message by [`log!`] with [`LogLevel::Warn`].
     #[macro_export]
     macro_rules! warn {
         ($($arg:tt)*) => {
-            let position = $crate::position!().to_string();
-            $crate::LOGGER.lock().await.warn(format!($($arg)*).as_str(), position).await;
+            $crate::log!($crate::LogLevel::Warn, $($arg)*)
         };
     }
 
     /// Macro to log info message.
-    /// First lock the logger in static, then log the message.
+    /// Log the message by [`log!`] with [`LogLevel::Info`].
     #[macro_export]
     macro_rules! info {
         ($($arg:tt)*) => {
-            let position = $crate::position!().to_string();
-            $crate::LOGGER.lock().await.info(format!($($arg)*).as_str(), position).await;
+            $crate::log!($crate::LogLevel::Info, $($arg)*)
         };
     }
 
     /// Macro to log debug message.
-    /// First lock the logger in static, then log the message.
+    /// Log the message by [`log!`] with [`LogLevel::Debug`].
     #[macro_export]
     macro_rules! debug {
         ($($arg:tt)*) => {
-            let position = $crate::position!().to_string();
-            $crate::LOGGER.lock().await.debug(format!($($arg)*).as_str(), position).await;
+            $crate::log!($crate::LogLevel::Debug, $($arg)*)
         };
     }
 
     /// Macro to log trace message.
-    /// First lock the logger in static, then log the message.
+    /// Log the message by [`log!`] with [`LogLevel::Trace`].
     #[macro_export]
     macro_rules! trace {
         ($($arg:tt)*) => {
-            let position = $crate::position!().to_string();
-            $crate::LOGGER.lock().await.trace(format!($($arg)*).as_str(), position).await;
+            $crate::log!($crate::LogLevel::Trace, $($arg)*)
         };
     }
 
     /// Define a macro named `log` with two parameters: `$level` and `$($arg:tt)*`
+    /// The sample rules are checked before the message is formatted,
+    /// and the logger is not locked while formatting, since the arguments may record logs as well.
+    /// With `err: error` before the message, the error with the chain of its sources (and a backtrace when captured)
+    /// is appended as continuation lines, see [`error_chain`].
+    /// With `value;` before the message, the [`LogFields`] of the value are appended as `key=value` pairs.
     #[macro_export]
     macro_rules! log {
         // Match the macro invocation with a level expression and a variable number of arguments
-        ($level:expr, $($arg:tt)*) => {
-            let position = $crate::position!().to_string();
-            $crate::LOGGER.lock().await.record($level, &format!($($arg)*), position).await;
-        }
+        ($level:expr, err: $err:expr, $($arg:tt)+) => {{
+            let level = $level;
+            if $crate::LOGGER.lock().await.sample(level, module_path!()) {
+                let position = $crate::position!();
+                let message = $crate::error_chain(&format!($($arg)+), &$err);
+                $crate::LOGGER.lock().await.record(level, &message, position).await;
+            }
+        }};
+        ($level:expr, $fields:expr; $($arg:tt)+) => {{
+            let level = $level;
+            if $crate::LOGGER.lock().await.sample(level, module_path!()) {
+                let position = $crate::position!();
+                let message = format!("{} {}", format!($($arg)+), $crate::LogFields::format_fields(&$fields));
+                $crate::LOGGER.lock().await.record(level, &message, position).await;
+            }
+        }};
+        ($level:expr, $($arg:tt)*) => {{
+            let level = $level;
+            if $crate::LOGGER.lock().await.sample(level, module_path!()) {
+                let position = $crate::position!();
+                let message = format!($($arg)*);
+                $crate::LOGGER.lock().await.record(level, &message, position).await;
+            }
+        }}
+    }
+
+    /// Macro to enter a [`Span`] with a level, a name and optional `key = value` fields.
+    /// The span logs its enter and exit, and prefixes the logs inside it with the span path.
+    /// It exits when the returned guard is dropped.
+    #[macro_export]
+    macro_rules! span {
+        ($level:expr, $name:expr $(, $key:ident = $value:expr)* $(,)?) => {
+            $crate::Span::new(
+                $level,
+                $name,
+                vec![$(format!("{}={:?}", stringify!($key), $value)),*],
+                $crate::position!(),
+            )
+            .enter()
+            .await
+        };
+    }
+
+    /// Write the pending logs, and flush them into the current log file.
+    pub async fn flush() {
+        let mut logger = LOGGER.lock().await;
+        logger.record_pending().await;
+        logger.flush().await;
+    }
+
+    /// Flush the logs and sync the current log file to the disk, so that they survive a crash or a power loss.
+    pub async fn sync() {
+        LOGGER.lock().await.sync().await;
     }
 
     /// Define a public asynchronous function named `enable_log`
@@ -159,74 +497,211 @@ mod async_log {
         // Call the `disable` method on the locked writer to disable logging
         writer.disable();
     }
+
+    /// Record a log from a synchronous context, such as a [`Drop`] implementation.
+    /// The log is queued in order, and written by a task spawned on the current runtime (or a temporary runtime if there is none),
+    /// or before the next log, [`flush`] or [`shutdown`], whichever comes first, so it is neither reordered nor lost.
+    pub(crate) fn record_detached(level: LogLevel, message: String, position: Location) {
+        detached::push(detached::Detached {
+            level,
+            message,
+            position,
+            span: span::current_path(),
+            context: context::Context::capture_all(),
+        });
+        let task = async {
+            LOGGER.lock().await.record_pending().await;
+        };
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(task);
+            }
+            Err(_) => tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("Cannot build the runtime.")
+                .block_on(task),
+        }
+    }
+
+    /// Shut down the logger outside of any async context, such as in [`Drop`].
+    /// A new thread with its own runtime is used, since the current runtime may be blocked or shutting down.
+    pub(crate) fn shutdown_detached() {
+        let _ = std::thread::spawn(|| {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .expect("Cannot build the runtime.")
+                .block_on(shutdown())
+        })
+        .join();
+    }
 }
 
 #[cfg(not(feature = "async"))]
 mod log {
     use super::*;
 
+    /// Macro to log fatal message.
+    /// Log the message by [`log!`] with [`LogLevel::Fatal`].
+    #[macro_export]
+    macro_rules! fatal {
+        ($($arg:tt)*) => {
+            $crate::log!($crate::LogLevel::Fatal, $($arg)*)
+        };
+    }
+
     /// Macro to log error message.
-    /// First lock the logger in static, then log the message.
+    /// Log the message by [`log!`] with [`LogLevel::Error`].
     #[macro_export]
     macro_rules! error {
         ($($arg:tt)*) => {
-            let position = $crate::position!().to_string();
-            $crate::LOGGER.lock().expect("Cannot lock the logger.").error(&format!($($arg)*), position);
+            $crate::log!($crate::LogLevel::Error, $($arg)*)
         };
     }
 
     /// Macro to log warning message.
-    /// First lock the logger in static, then log the message.
+    /// Log the message by [`log!`] with [`LogLevel::Warn`].
     #[macro_export]
     macro_rules! warn {
         ($($arg:tt)*) => {
-            let position = $crate::position!().to_string();
-            $crate::LOGGER.lock().expect("Cannot lock the logger.").warn(&format!($($arg)*), position);
+            $crate::log!($crate::LogLevel::Warn, $($arg)*)
         };
     }
 
     /// Macro to log info message.
-    /// First lock the logger in static, then log the message.
+    /// Log the message by [`log!`] with [`LogLevel::Info`].
     #[macro_export]
     macro_rules! info {
         ($($arg:tt)*) => {
-            let position = $crate::position!().to_string();
-            $crate::LOGGER.lock().expect("Cannot lock the logger.").info(&format!($($arg)*), position);
+            $crate::log!($crate::LogLevel::Info, $($arg)*)
         };
     }
 
     /// Macro to log debug message.
-    /// First lock the logger in static, then log the message.
+    /// Log the message by [`log!`] with [`LogLevel::Debug`].
     #[macro_export]
     macro_rules! debug {
         ($($arg:tt)*) => {
-            let position = $crate::position!().to_string();
-            $crate::LOGGER.lock().expect("Cannot lock the logger.").debug(&format!($($arg)*), position);
+            $crate::log!($crate::LogLevel::Debug, $($arg)*)
         };
     }
 
     /// Macro to log trace message.
-    /// First lock the logger in static, then log the message.
+    /// Log the message by [`log!`] with [`LogLevel::Trace`].
     #[macro_export]
     macro_rules! trace {
         ($($arg:tt)*) => {
-            let position = $crate::position!().to_string();
-            $crate::LOGGER.lock().expect("Cannot lock the logger.").trace(&format!($($arg)*), position);
+            $crate::log!($crate::LogLevel::Trace, $($arg)*)
         };
     }
 
+    /// Macro to log message with a given [`LogLevel`].
+    /// First check the sample rules before formatting, then format the message and log it.
+    /// The logger is not locked while formatting, since the arguments may record logs as well.
+    /// With `err: error` before the message, the error with the chain of its sources (and a backtrace when captured)
+    /// is appended as continuation lines, see [`error_chain`].
+    /// With `value;` before the message, the [`LogFields`] of the value are appended as `key=value` pairs.
     #[macro_export]
     macro_rules! log {
-        ($level:expr, $($arg:tt)*) => {
-            let position = $crate::position!().to_string();
-            $crate::LOGGER.lock().expect("Cannot lock the logger.").record($level, &format!($($arg)*), position);
-        }
+        ($level:expr, err: $err:expr, $($arg:tt)+) => {{
+            let level = $level;
+            let sampled = $crate::LOGGER
+                .lock()
+                .expect("Cannot lock the logger.")
+                .sample(level, module_path!());
+            if sampled {
+                let position = $crate::position!();
+                let message = $crate::error_chain(&format!($($arg)+), &$err);
+                $crate::LOGGER
+                    .lock()
+                    .expect("Cannot lock the logger.")
+                    .record(level, &message, position);
+            }
+        }};
+        ($level:expr, $fields:expr; $($arg:tt)+) => {{
+            let level = $level;
+            let sampled = $crate::LOGGER
+                .lock()
+                .expect("Cannot lock the logger.")
+                .sample(level, module_path!());
+            if sampled {
+                let position = $crate::position!();
+                let message = format!("{} {}", format!($($arg)+), $crate::LogFields::format_fields(&$fields));
+                $crate::LOGGER
+                    .lock()
+                    .expect("Cannot lock the logger.")
+                    .record(level, &message, position);
+            }
+        }};
+        ($level:expr, $($arg:tt)*) => {{
+            let level = $level;
+            let sampled = $crate::LOGGER
+                .lock()
+                .expect("Cannot lock the logger.")
+                .sample(level, module_path!());
+            if sampled {
+                let position = $crate::position!();
+                let message = format!($($arg)*);
+                $crate::LOGGER
+                    .lock()
+                    .expect("Cannot lock the logger.")
+                    .record(level, &message, position);
+            }
+        }}
+    }
+
+    /// Macro to enter a [`Span`] with a level, a name and optional `key = value` fields.
+    /// The span logs its enter and exit, and prefixes the logs inside it with the span path.
+    /// It exits when the returned guard is dropped.
+    #[macro_export]
+    macro_rules! span {
+        ($level:expr, $name:expr $(, $key:ident = $value:expr)* $(,)?) => {
+            $crate::Span::new(
+                $level,
+                $name,
+                vec![$(format!("{}={:?}", stringify!($key), $value)),*],
+                $crate::position!(),
+            )
+            .enter()
+        };
     }
 
     /// Initialize the static logger with customized setting.
     pub fn init(setting: Setting) {
         let mut logger = LOGGER.lock().unwrap();
         logger.init(setting);
+        if let Some(period) = logger.tick_period() {
+            start_timer(period);
+        }
+    }
+
+    /// Initialize the static logger with customized setting,
+    /// and get a guard which shuts down the logger by [`shutdown`] when it is dropped.
+    pub fn init_with_guard(setting: Setting) -> LoggerGuard {
+        init(setting);
+        LoggerGuard::new()
+    }
+
+    /// Shut down the logger: write the summary of the repeated logs, the shutdown log if `shutdown_record` is set,
+    /// and the signature of the logs not signed yet, then flush and sync the current log file.
+    /// Call it before [`std::process::exit`], which does not drop the [`LoggerGuard`].
+    pub fn shutdown() {
+        LOGGER
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .shutdown();
+    }
+
+    /// Start the thread doing the periodic work of the logger, once per process.
+    fn start_timer(period: Duration) {
+        if TIMER.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        std::thread::spawn(move || loop {
+            std::thread::sleep(period);
+            LOGGER.lock().expect("Cannot lock the logger.").tick();
+        });
     }
 
     /// Provide a easier way to clean all the existed logs.
@@ -235,6 +710,16 @@ mod log {
         writer.clear_dir();
     }
 
+    /// Flush the logs written into the current log file.
+    pub fn flush() {
+        LOGGER.lock().expect("Cannot lock the logger.").flush();
+    }
+
+    /// Flush the logs and sync the current log file to the disk, so that they survive a crash or a power loss.
+    pub fn sync() {
+        LOGGER.lock().expect("Cannot lock the logger.").sync();
+    }
+
     /// Public function to enable logging
     pub fn enable_log() {
         // Lock the LOGGER to ensure thread-safe access
@@ -250,36 +735,17 @@ mod log {
         // Disable logging using the writer
         writer.disable();
     }
-}
-
-/// Enumeration of log levels.
-/// This defines the emergency of the log.
-/// (the corresponding number is used to compare the log level to decide write to the log file or not.)
-#[derive(Copy, Clone, Debug)]
-pub enum LogLevel {
-    Trace = 0,
-    Debug = 1,
-    Info = 2,
-    Warn = 3,
-    Error = 4,
-}
 
-impl std::fmt::Display for LogLevel {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            LogLevel::Info => write!(f, "INFO "),
-            LogLevel::Debug => write!(f, "DEBUG"),
-            LogLevel::Warn => write!(f, "WARN "),
-            LogLevel::Error => write!(f, "ERROR"),
-            LogLevel::Trace => write!(f, "TRACE"),
-        }
+    /// Record a log from a context that cannot use the macros, such as a [`Drop`] implementation.
+    pub(crate) fn record_detached(level: LogLevel, message: String, position: Location) {
+        let mut writer = LOGGER
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        writer.record(level, &message, position);
     }
-}
 
-impl LogLevel {
-    pub fn get_level(&self) -> usize {
-        *self as usize
+    /// Shut down the logger, such as in [`Drop`].
+    pub(crate) fn shutdown_detached() {
+        shutdown();
     }
 }
-
-unsafe impl Send for LogLevel {}
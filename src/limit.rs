@@ -0,0 +1,114 @@
+use super::{Location, LogLevel};
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, Instant};
+
+/// A limit of the logs' rate, applied by a token bucket.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RateLimit {
+    /// the number of logs allowed per second in average.
+    pub per_second: f64,
+    /// the number of logs allowed in a burst.
+    pub burst: u32,
+}
+
+impl RateLimit {
+    /// Allow `count` logs per second, and a burst of the same size.
+    pub fn per_second(count: u32) -> Self {
+        Self {
+            per_second: count as f64,
+            burst: count,
+        }
+    }
+}
+
+/// A token bucket, refilled continuously with the rate of a [`RateLimit`].
+#[derive(Debug)]
+struct TokenBucket {
+    /// the tokens left in the bucket.
+    tokens: f64,
+    /// the last time the bucket was refilled.
+    refilled: Instant,
+}
+
+impl TokenBucket {
+    fn new(limit: &RateLimit) -> Self {
+        Self {
+            tokens: limit.burst as f64,
+            refilled: Instant::now(),
+        }
+    }
+
+    /// Refill the bucket and try to take a token from it.
+    fn take(&mut self, limit: &RateLimit) -> bool {
+        let now = Instant::now();
+        let passed = now.duration_since(self.refilled).as_secs_f64();
+        self.tokens = (self.tokens + passed * limit.per_second).min(limit.burst as f64);
+        self.refilled = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// The state of the rate limits, and the count of the dropped logs of each position.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    /// the buckets of each call site.
+    sites: HashMap<Location, TokenBucket>,
+    /// the bucket shared by all logs.
+    global: Option<TokenBucket>,
+    /// the count of the dropped logs of each position since the last report.
+    dropped: BTreeMap<Location, usize>,
+    /// the last time the dropped logs were reported.
+    reported: Instant,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self {
+            sites: HashMap::new(),
+            global: None,
+            dropped: BTreeMap::new(),
+            reported: Instant::now(),
+        }
+    }
+}
+
+impl RateLimiter {
+    /// Check whether a log from the position is allowed, and count it if it is dropped.
+    pub(crate) fn check(
+        &mut self,
+        level: LogLevel,
+        position: Location,
+        limits: &HashMap<LogLevel, RateLimit>,
+        global: Option<&RateLimit>,
+    ) -> bool {
+        let allowed = limits.get(&level).is_none_or(|limit| {
+            self.sites
+                .entry(position)
+                .or_insert_with(|| TokenBucket::new(limit))
+                .take(limit)
+        }) && global.is_none_or(|limit| {
+            self.global
+                .get_or_insert_with(|| TokenBucket::new(limit))
+                .take(limit)
+        });
+
+        if !allowed {
+            *self.dropped.entry(position).or_default() += 1;
+        }
+        allowed
+    }
+
+    /// Take the count of the dropped logs of each position, if the interval since the last report has passed.
+    pub(crate) fn report(&mut self, interval: Duration) -> Vec<(Location, usize)> {
+        if self.dropped.is_empty() || self.reported.elapsed() < interval {
+            return Vec::new();
+        }
+        self.reported = Instant::now();
+        std::mem::take(&mut self.dropped).into_iter().collect()
+    }
+}
@@ -0,0 +1,105 @@
+use super::Setting;
+
+/// The location in the code where a log is emitted, captured by [`position!`](crate::position).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Location {
+    /// the module path, given by `module_path!()`.
+    pub module_path: &'static str,
+    /// the full path of the function, given by [`func_path!`](crate::func_path).
+    pub function: &'static str,
+    /// the file, given by `file!()`.
+    pub file: &'static str,
+    /// the line, given by `line!()`.
+    pub line: u32,
+    /// the column, given by `column!()`.
+    pub column: u32,
+}
+
+/// How much of the [`Location`] is shown in the log.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum PositionFormat {
+    /// the short function name, the file, the line and the column. (`new @ src/main.rs:14:5`)
+    #[default]
+    Short,
+    /// the full function path, the file, the line and the column. (`app::db::Pool::new @ src/db.rs:14:5`)
+    Full,
+    /// the module path, the file, the line and the column. (`app::db @ src/db.rs:14:5`)
+    Module,
+    /// only the file, the line and the column. (`src/db.rs:14:5`)
+    File,
+    /// only the full function path. (`app::db::Pool::new`)
+    Function,
+}
+
+impl Location {
+    /// Get the location of the caller, for the functions with `#[track_caller]`.
+    /// The module path and the function are unknown, so they are empty.
+    #[track_caller]
+    pub fn caller() -> Self {
+        let caller = std::panic::Location::caller();
+        Self {
+            module_path: "",
+            function: "",
+            file: caller.file(),
+            line: caller.line(),
+            column: caller.column(),
+        }
+    }
+
+    /// Get the short name of the function, which is the last segment of the path that is not a closure.
+    pub fn short_function(&self) -> &'static str {
+        self.function
+            .rsplit("::")
+            .find(|&part| part != "{{closure}}")
+            .unwrap_or(self.function)
+    }
+
+    /// Format the location with the given format and file path.
+    pub(crate) fn format(&self, format: PositionFormat, file: &str) -> String {
+        let function = match format {
+            _ if self.function.is_empty() => "",
+            PositionFormat::Short => self.short_function(),
+            PositionFormat::Full | PositionFormat::Function => self.function,
+            PositionFormat::Module => self.module_path,
+            PositionFormat::File => "",
+        };
+        match format {
+            PositionFormat::Function if !function.is_empty() => function.to_string(),
+            _ if function.is_empty() => format!("{}:{}:{}", file, self.line, self.column),
+            _ => format!("{} @ {}:{}:{}", function, file, self.line, self.column),
+        }
+    }
+}
+
+/// Rewrite the file path of a position by the path settings,
+/// so that the logs from the binaries built on different hosts look identical.
+/// The separators are normalised first, then the first matching remap is applied, and the prefix is stripped last.
+pub(crate) fn remap_file(file: &str, setting: &Setting) -> String {
+    let mut file = if setting.normalize_path_separators {
+        file.replace('\\', "/")
+    } else {
+        file.to_string()
+    };
+    if let Some((from, to)) = setting
+        .path_remap
+        .iter()
+        .find(|(from, _)| file.starts_with(from.as_str()))
+    {
+        file = format!("{}{}", to, &file[from.len()..]);
+    }
+    if let Some(rest) = setting.path_strip_prefix.as_ref().and_then(|prefix| {
+        // only strip whole components, so `/src/app` is not stripped from `/src/application`.
+        let rest = file.strip_prefix(prefix.as_str())?;
+        (prefix.ends_with(['/', '\\']) || rest.is_empty() || rest.starts_with(['/', '\\']))
+            .then_some(rest)
+    }) {
+        file = rest.trim_start_matches(['/', '\\']).to_string();
+    }
+    file
+}
+
+impl std::fmt::Display for Location {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.format(PositionFormat::Short, self.file))
+    }
+}
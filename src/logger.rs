@@ -1,9 +1,27 @@
-use super::{msg::LogMessage, position, LogLevel, Setting};
-use chrono::FixedOffset;
+#[cfg(feature = "async")]
+use super::detached;
+#[cfg(feature = "encryption")]
+use super::encrypt::{self, Encryptor};
+#[cfg(feature = "redaction")]
+use super::redact::Redactor;
+#[cfg(feature = "integrity")]
+use super::{chain, signing, SecretKey};
+use super::{
+    context::Context,
+    dedup::Dedup,
+    limit::RateLimiter,
+    msg::{sanitize, LogMessage},
+    position,
+    sample::Sampler,
+    span,
+    time::Elapsed,
+    Location, LogLevel, Setting, SyncPolicy,
+};
 #[cfg(not(feature = "async"))]
 use std::fs::{self, File};
 #[cfg(not(feature = "async"))]
 use std::io::Write;
+use std::time::{Duration, Instant};
 #[cfg(feature = "async")]
 use tokio::fs::{self, File};
 #[cfg(feature = "async")]
@@ -24,6 +42,37 @@ pub struct Logger {
     init: bool,
     /// setting of the logger.
     setting: Setting,
+    /// the state of suppressing the repeated logs.
+    dedup: Dedup,
+    /// the state of the rate limits.
+    limiter: RateLimiter,
+    /// the state of the sample rules.
+    sampler: Sampler,
+    /// the compiled redact rules.
+    #[cfg(feature = "redaction")]
+    redactor: Redactor,
+    /// the hash of the last line written, if the hash chain is enabled. (`None` until it is read from the directory)
+    #[cfg(feature = "integrity")]
+    chain: Option<String>,
+    /// the sequence number of the next log, if the signing key is set. (`None` until it is read from the directory)
+    #[cfg(feature = "integrity")]
+    sequence: Option<u64>,
+    /// the logs not signed yet.
+    #[cfg(feature = "integrity")]
+    block: Vec<String>,
+    /// the state of encrypting the log files, if the encryption key is set and valid.
+    #[cfg(feature = "encryption")]
+    encryptor: Option<Encryptor>,
+    /// the logs not written into the file yet.
+    buffer: Vec<u8>,
+    /// when the first log in the buffer was buffered.
+    buffered_since: Option<Instant>,
+    /// the number of logs written since the last sync.
+    unsynced: usize,
+    /// when the logs were synced last time.
+    last_sync: Instant,
+    /// when the logger started, used to measure the monotonic time of the logs.
+    started: Instant,
 }
 
 impl Logger {
@@ -38,13 +87,26 @@ impl Logger {
             current_index: 0,
             used_length: 0,
             init: false,
-            current_file_prefix: format!(
-                "{}",
-                chrono::Utc::now()
-                    .with_timezone(&FixedOffset::east_opt(setting.time_zone * 3600).unwrap())
-                    .format(&setting.file_time_format)
-            ),
+            current_file_prefix: setting.time_zone.format_now(&setting.file_time_format),
             setting,
+            dedup: Dedup::default(),
+            limiter: RateLimiter::default(),
+            sampler: Sampler::default(),
+            #[cfg(feature = "redaction")]
+            redactor: Redactor::default(),
+            #[cfg(feature = "integrity")]
+            chain: None,
+            #[cfg(feature = "integrity")]
+            sequence: None,
+            #[cfg(feature = "integrity")]
+            block: Vec::new(),
+            #[cfg(feature = "encryption")]
+            encryptor: None,
+            buffer: Vec::new(),
+            buffered_since: None,
+            unsynced: 0,
+            last_sync: Instant::now(),
+            started: Instant::now(),
         };
         buffer.current_index = buffer.get_index_not_async(&buffer.current_file_prefix);
         buffer
@@ -52,9 +114,25 @@ impl Logger {
 
     /// Get the path of the log file.
     fn get_path(&self, time_prefix: &str, index: usize) -> String {
+        #[cfg(feature = "encryption")]
+        if self.setting.encryption_key.is_some() {
+            return format!(
+                "{}/{}_{}{}",
+                self.setting.dir_path,
+                time_prefix,
+                index,
+                encrypt::EXTENSION
+            );
+        }
         format!("{}/{}_{}.log", self.setting.dir_path, time_prefix, index)
     }
 
+    /// Get the paths of the log file, both plain and encrypted, to check whether the index is used.
+    fn get_paths(&self, time_prefix: &str, index: usize) -> [String; 2] {
+        let path = format!("{}/{}_{}.log", self.setting.dir_path, time_prefix, index);
+        [path.clone(), path + ".enc"]
+    }
+
     /// Disable the logger.
     pub(crate) fn disable(&mut self) {
         self.setting.disabled = true;
@@ -70,9 +148,9 @@ impl Logger {
     fn get_index_not_async(&self, time_prefix: &str) -> usize {
         let mut count = 0;
         loop {
-            let path = self.get_path(time_prefix, count);
+            let paths = self.get_paths(time_prefix, count);
             // if the file exists, then the index is the next one
-            if let Ok(_) = std::fs::File::open(path) {
+            if paths.iter().any(|path| std::fs::File::open(path).is_ok()) {
                 count += 1
             } else {
                 return count;
@@ -80,12 +158,307 @@ impl Logger {
         }
     }
 
+    /// Check whether a log of the level from the module is kept by the sample rules.
+    /// This is cheap, and is called by the macros before the message is formatted.
+    pub fn sample(&mut self, log_level: LogLevel, module_path: &str) -> bool {
+        self.sampler
+            .check(&self.setting.sampling, log_level, module_path)
+    }
+
+    /// Get the time prefix of the log files' name at the current time.
+    fn time_prefix(&self) -> String {
+        self.setting
+            .time_zone
+            .format_now(&self.setting.file_time_format)
+    }
+
+    /// Check the setting, and fall back to the default value of the invalid items.
+    /// Returns the errors to be recorded, instead of panicking when writing.
+    fn check_setting(&mut self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if let Err(error) = self.setting.time_zone.validate() {
+            self.setting.time_zone = Default::default();
+            errors.push(format!("{}, UTC is used instead.", error));
+        }
+        if let Err(error) = self.setting.time_format.validate() {
+            self.setting.time_format = Default::default();
+            errors.push(format!("{}, the standard format is used instead.", error));
+        }
+        #[cfg(feature = "redaction")]
+        {
+            let (redactor, redact_errors) =
+                Redactor::compile(&self.setting.redact_rules, self.setting.redact_key.as_ref());
+            self.redactor = redactor;
+            errors.extend(redact_errors);
+        }
+        #[cfg(feature = "integrity")]
+        if self
+            .setting
+            .signing_key
+            .as_ref()
+            .is_some_and(SecretKey::is_empty)
+        {
+            self.setting.signing_key = None;
+            errors.push("The signing key is empty, the logs are not signed.".to_string());
+        }
+        #[cfg(feature = "encryption")]
+        {
+            self.encryptor = None;
+            if let Some(key) = &self.setting.encryption_key {
+                match Encryptor::new(key) {
+                    Ok(encryptor) => self.encryptor = Some(encryptor),
+                    Err(error) => {
+                        let error = format!("{}, the logs are not written into the files.", error);
+                        // the error log is not written into the files either, so it is surfaced here
+                        eprintln!("{}", error);
+                        errors.push(error);
+                    }
+                }
+            }
+        }
+        #[cfg(feature = "integrity")]
+        if self.setting.signing_block == 0 {
+            self.setting.signing_block = 1;
+            errors.push("The signing block is 0, each log is signed instead.".to_string());
+        }
+        if self.setting.buffer_size > 0 && self.setting.buffer_latency.is_zero() {
+            self.setting.buffer_size = 0;
+            errors.push("The buffer latency is 0, the logs are not buffered.".to_string());
+        }
+        errors
+    }
+
+    /// Get the text of the log to write into the file.
+    /// With the signing key, the sequence number is appended, and a signature follows each block of logs.
+    fn file_line(&mut self, msg: &LogMessage) -> Vec<u8> {
+        let lines = self.signed_lines(msg.print(&self.setting));
+        let text = self.file_text(lines);
+        self.file_bytes(text)
+    }
+
+    /// Get the lines to write for the log.
+    /// With the signing key, the sequence number is appended, and the signature follows when the block is full.
+    #[cfg(feature = "integrity")]
+    fn signed_lines(&mut self, line: String) -> Vec<String> {
+        let mut lines = vec![line];
+        if self.setting.signing_key.is_some() {
+            let sequence = match self.sequence {
+                Some(sequence) => sequence,
+                None => signing::next_sequence(&self.setting),
+            };
+            self.sequence = Some(sequence + 1);
+            lines[0] = format!("{}{}{}", lines[0], signing::SEQUENCE, sequence);
+            self.block.push(lines[0].clone());
+            if self.block.len() >= self.setting.signing_block {
+                lines.extend(self.close_block());
+            }
+        }
+        lines
+    }
+
+    /// Get the lines to write for the log.
+    #[cfg(not(feature = "integrity"))]
+    fn signed_lines(&mut self, line: String) -> Vec<String> {
+        vec![line]
+    }
+
+    /// Get the signature line of the logs not signed yet.
+    #[cfg(feature = "integrity")]
+    fn close_block(&mut self) -> Option<String> {
+        let key = self.setting.signing_key.as_ref()?;
+        let next = self.sequence?;
+        if self.block.is_empty() {
+            return None;
+        }
+        let first = next - self.block.len() as u64;
+        let signature = signing::sign(key.as_bytes(), first, next - 1, &self.block);
+        self.block.clear();
+        Some(format!(
+            "{}{}-{} {}",
+            signing::SIGNATURE,
+            first,
+            next - 1,
+            signature
+        ))
+    }
+
+    /// Get the signature line of the logs not signed yet.
+    #[cfg(not(feature = "integrity"))]
+    fn close_block(&mut self) -> Option<String> {
+        None
+    }
+
+    /// Get the signature of the logs not signed yet, to write before the file is closed.
+    fn close_file(&mut self) -> Option<Vec<u8>> {
+        let signature = self.close_block()?;
+        let text = self.file_text(vec![signature]);
+        Some(self.file_bytes(text))
+    }
+
+    /// Get the bytes of the text to write into the file, which are encrypted if the encryption key is set.
+    /// Nothing is written if the key is invalid.
+    fn file_bytes(&mut self, text: String) -> Vec<u8> {
+        #[cfg(feature = "encryption")]
+        if self.setting.encryption_key.is_some() {
+            let path = self.get_path(&self.current_file_prefix, self.current_index);
+            return match &mut self.encryptor {
+                Some(encryptor) => encryptor.encrypt(path, &text),
+                None => Vec::new(),
+            };
+        }
+        text.into_bytes()
+    }
+
+    /// Check whether the logs are written into the files, which they are not if the encryption key is invalid.
+    fn writes_files(&self) -> bool {
+        #[cfg(feature = "encryption")]
+        if self.setting.encryption_key.is_some() && self.encryptor.is_none() {
+            return false;
+        }
+        true
+    }
+
+    /// Get the text of the lines to write into the file.
+    /// With the hash chain enabled, the hash is appended, and the header is written before the first log of each file.
+    fn file_text(&mut self, lines: Vec<String>) -> String {
+        #[cfg(feature = "integrity")]
+        if self.setting.hash_chain {
+            return self.chained_text(lines);
+        }
+        let mut text = String::new();
+        for line in lines {
+            text += &line;
+            text += "\n";
+        }
+        text
+    }
+
+    /// Get the text of the lines with the hashes appended, and the header before the first log of each file.
+    #[cfg(feature = "integrity")]
+    fn chained_text(&mut self, lines: Vec<String>) -> String {
+        let mut text = String::new();
+        let mut previous = match self.chain.take() {
+            Some(previous) => previous,
+            None => chain::last_hash(&self.setting),
+        };
+        if self.used_length == 0 {
+            text += &format!("{}{}\n", chain::HEADER, previous);
+        }
+        for line in lines {
+            previous = chain::link(&previous, &line);
+            text += &format!("{} #{}\n", line, previous);
+        }
+        self.chain = Some(previous);
+        text
+    }
+
+    /// Buffer the bytes to write into the current file.
+    fn buffer(&mut self, bytes: Vec<u8>) {
+        if self.buffer.is_empty() {
+            self.buffered_since = Some(Instant::now());
+        }
+        self.buffer.extend_from_slice(&bytes);
+    }
+
+    /// Check whether the buffered logs have been buffered for the max latency.
+    fn buffer_due(&self) -> bool {
+        self.buffered_since
+            .is_some_and(|since| since.elapsed() >= self.setting.buffer_latency)
+    }
+
+    /// Check whether the buffered logs should be written, after a log of the level is buffered.
+    /// They are written when the buffer is full, the max latency has passed, or the level is error or higher.
+    fn should_write_buffer(&self, log_level: LogLevel) -> bool {
+        self.buffer.len() >= self.setting.buffer_size
+            || self.buffer_due()
+            || (log_level != LogLevel::Off && log_level.is_at_least(LogLevel::Error))
+    }
+
+    /// Check whether the logs should be synced to the disk by the sync policy, after a log of the level is written.
+    fn should_sync(&self, log_level: LogLevel) -> bool {
+        match self.setting.sync_policy {
+            SyncPolicy::Never => false,
+            SyncPolicy::Always => true,
+            SyncPolicy::EveryRecords(n) => self.unsynced >= n,
+            SyncPolicy::Interval(interval) => self.last_sync.elapsed() >= interval,
+            SyncPolicy::Level(level) => log_level != LogLevel::Off && log_level.is_at_least(level),
+        }
+    }
+
+    /// Check whether the logs written since the last sync should be synced by the interval of the sync policy,
+    /// when no log is recorded to trigger it.
+    fn should_sync_by_interval(&self) -> bool {
+        matches!(self.setting.sync_policy, SyncPolicy::Interval(interval)
+            if self.unsynced > 0 && self.last_sync.elapsed() >= interval)
+    }
+
+    /// Create a log message with the current setting.
+    fn new_message(&self, log_level: LogLevel, message: String, position: Location) -> LogMessage {
+        let mut msg = LogMessage::new(log_level, message, &self.setting.time_zone, position);
+        msg.time.detailed_display = self.setting.time_detailed_display;
+        msg.context = Context::capture(&self.setting);
+        msg.elapsed = Elapsed::measure(
+            self.started,
+            self.setting.record_elapsed,
+            self.setting.record_delta,
+        );
+        msg
+    }
+
+    /// Check whether the log is allowed by the rate limits.
+    fn rate_limit(&mut self, log_level: LogLevel, position: Location) -> bool {
+        self.limiter.check(
+            log_level,
+            position,
+            &self.setting.rate_limits,
+            self.setting.global_rate_limit.as_ref(),
+        )
+    }
+
+    /// Get the logs reporting how many logs have been dropped by the rate limits, if it is time to report.
+    fn rate_limit_report(&mut self) -> Vec<LogMessage> {
+        self.limiter
+            .report(self.setting.rate_limit_report_interval)
+            .into_iter()
+            .map(|(position, count)| {
+                self.new_message(
+                    LogLevel::Warn,
+                    format!("{} logs suppressed by the rate limit", count),
+                    position,
+                )
+            })
+            .collect()
+    }
+
     /// check the dir if it exists. if not, create it
     fn check_dir(&self) {
         if !std::path::Path::new(&self.setting.dir_path).exists() {
             std::fs::create_dir(&self.setting.dir_path).expect("Failed to create directory");
         }
     }
+
+    /// Get the period of the timer doing the periodic work, if there is any:
+    /// writing the buffered logs, summarizing the runs of repeats when the window expires,
+    /// reporting the logs dropped by the rate limits, and syncing the logs by the interval.
+    pub(crate) fn tick_period(&self) -> Option<Duration> {
+        if !self.init {
+            return None;
+        }
+        [
+            (self.setting.buffer_size > 0).then_some(self.setting.buffer_latency),
+            self.setting.dedup_window,
+            (!self.setting.rate_limits.is_empty() || self.setting.global_rate_limit.is_some())
+                .then_some(self.setting.rate_limit_report_interval),
+            match self.setting.sync_policy {
+                SyncPolicy::Interval(interval) => Some(interval),
+                _ => None,
+            },
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|period| !period.is_zero())
+        .min()
+    }
 }
 
 #[cfg(feature = "async")]
@@ -93,7 +466,7 @@ impl Logger {
     /// Customize and initialize the log writer.
     pub(crate) async fn init(&mut self, setting: Setting) {
         if self.init {
-            let position = position!().to_string();
+            let position = position!();
             self.warn("Log writer had been initialized!", position)
                 .await;
             return;
@@ -101,31 +474,64 @@ impl Logger {
 
         self.file = None;
         self.used_length = 0;
+        self.dedup = Dedup::default();
+        self.limiter = RateLimiter::default();
+        self.sampler = Sampler::default();
+        #[cfg(feature = "integrity")]
+        {
+            self.chain = None;
+            self.sequence = None;
+            self.block.clear();
+        }
+        self.buffer.clear();
+        self.buffered_since = None;
 
         self.setting = setting;
+        let errors = self.check_setting();
 
         self.init = true;
-        self.current_file_prefix = format!(
-            "{}",
-            chrono::Utc::now()
-                .with_timezone(&FixedOffset::east_opt(self.setting.time_zone * 3600).unwrap())
-                .format("%Y-%m-%d")
-        );
+        self.current_file_prefix = self.time_prefix();
         self.check_dir();
         self.current_index = self.get_index(&self.current_file_prefix).await;
+
+        for error in errors {
+            self.error(&error, position!()).await;
+        }
     }
 
     /// clear the log directory. (remove all the log files in the directory)
     pub(crate) async fn clear_dir(&mut self) {
-        fs::remove_dir_all(&self.setting.dir_path)
+        if fs::try_exists(&self.setting.dir_path)
             .await
-            .expect("Cannot remove the dir.");
+            .unwrap_or(false)
+        {
+            fs::remove_dir_all(&self.setting.dir_path)
+                .await
+                .expect("Cannot remove the dir.");
+        }
         fs::create_dir(&self.setting.dir_path)
             .await
             .expect("Cannot create the dir.");
+        if self.setting.sync_policy != SyncPolicy::Never {
+            self.sync_dir().await;
+        }
         self.current_index = 0;
         self.used_length = 0;
         self.file = None;
+        #[cfg(feature = "integrity")]
+        {
+            self.chain = None;
+            self.sequence = None;
+            self.block.clear();
+        }
+        self.buffer.clear();
+        self.buffered_since = None;
+        #[cfg(feature = "encryption")]
+        if let Some(encryptor) = self.encryptor.as_mut() {
+            encryptor.reset();
+        }
+        self.current_file_prefix = self.time_prefix();
+        self.current_file_prefix = self.time_prefix();
     }
 
     /// Write a single log message to the file.
@@ -135,20 +541,25 @@ impl Logger {
             return;
         }
 
-        for i in msg.split_enter() {
-            if self.file.is_none() {
+        // no file is created if the logs cannot be written into it
+        let to_file = self.writes_files();
+        let mut written = false;
+        for i in msg.split_enter(self.setting.sanitize_messages) {
+            if to_file && self.file.is_none() {
                 self.file = Some(self.get_file().await);
             }
 
             // check if the time prefix has changed
             // (when a new day begins)
-            let time_prefix = format!(
-                "{}",
-                chrono::Utc::now()
-                    .with_timezone(&FixedOffset::east_opt(self.setting.time_zone * 3600).unwrap())
-                    .format("%Y-%m-%d")
-            );
-            if self.current_file_prefix != time_prefix {
+            let time_prefix = self.time_prefix();
+            if to_file && self.current_file_prefix != time_prefix {
+                if let Some(bytes) = self.close_file() {
+                    self.buffer(bytes);
+                }
+                self.write_buffer().await;
+                if self.setting.sync_policy != SyncPolicy::Never {
+                    self.sync().await;
+                }
                 self.current_file_prefix = time_prefix;
                 self.current_index = self.get_index(&self.current_file_prefix).await;
                 self.used_length = 0;
@@ -158,26 +569,47 @@ impl Logger {
             // check if should print to terminal.
             // requirement: print out is enabled and the level is high enough
             if self.setting.print_out
-                && self.setting.terminal_print_level.get_level() <= i.get_level()
+                && i.get_level() != LogLevel::Off
+                && i.get_level().is_at_least(self.setting.terminal_print_level)
             {
-                println!("{}", i.print())
+                println!("{}", i.print(&self.setting))
             };
 
             // check if should write to file.
-            // requirement: the level is high enough
-            if self.setting.file_record_level.get_level() <= i.get_level() {
-                self.file
-                    .as_mut()
-                    .unwrap()
-                    .write_all((i.print() + "\n").as_bytes())
-                    .await
-                    .expect("Cannot write into the log file.");
+            // requirement: the logs can be written into the files,
+            // and the level is high enough, or the level is always recorded
+            if to_file
+                && (i.get_level().is_always_recorded()
+                    || (i.get_level() != LogLevel::Off
+                        && i.get_level().is_at_least(self.setting.file_record_level)))
+            {
+                let bytes = self.file_line(&i);
+                written = true;
+                self.buffer(bytes);
                 self.used_length += 1;
             };
         }
 
+        // check if the buffered logs should be written, and synced to the disk
+        if written {
+            self.unsynced += 1;
+            if self.should_write_buffer(msg.get_level()) {
+                self.write_buffer().await;
+            }
+            if self.should_sync(msg.get_level()) {
+                self.sync().await;
+            }
+        }
+
         // check if the file is full or unlimited size
         if self.setting.single_length != 0 && self.used_length >= self.setting.single_length {
+            if let Some(bytes) = self.close_file() {
+                self.buffer(bytes);
+            }
+            self.write_buffer().await;
+            if self.setting.sync_policy != SyncPolicy::Never {
+                self.sync().await;
+            }
             self.current_index += 1;
             self.used_length = 0;
             self.file = None;
@@ -185,56 +617,192 @@ impl Logger {
     }
 
     /// provide a method to log something by only a given string and [`LogLevel`].
-    pub async fn record(&mut self, log_level: LogLevel, message: &str, position: String) {
+    pub async fn record(&mut self, log_level: LogLevel, message: &str, position: Location) {
+        self.record_pending().await;
+        self.record_in_span(log_level, message, position, span::current_path(), None)
+            .await;
+    }
+
+    /// Record a log inside the given span path, which is captured at the call site,
+    /// with the context captured at the call site if it is not the current one.
+    pub(crate) async fn record_in_span(
+        &mut self,
+        log_level: LogLevel,
+        message: &str,
+        position: Location,
+        span: Option<String>,
+        context: Option<Context>,
+    ) {
         if !self.init {
             self.init = true
         }
-        let mut msg = LogMessage::new(
-            log_level,
-            message.to_string(),
-            self.setting.time_zone,
-            position,
-        );
-        msg.time.detailed_display = self.setting.time_detailed_display;
+        for report in self.rate_limit_report() {
+            self.write(&report).await;
+        }
+        if !self.rate_limit(log_level, position) {
+            return;
+        }
+        #[cfg(feature = "redaction")]
+        let mut message = self.redactor.redact(message).into_owned();
+        #[cfg(not(feature = "redaction"))]
+        let mut message = message.to_string();
+        if self.setting.sanitize_messages {
+            message = sanitize(&message);
+        }
+        let mut msg = self.new_message(log_level, message, position);
+        msg.span = span;
+        if let Some(context) = context {
+            msg.context = context.only(&self.setting);
+        }
+
+        if let Some(window) = self.setting.dedup_window {
+            let (summary, keep) = self.dedup.check(&msg, window);
+            if let Some(summary) = summary {
+                self.write(&summary).await;
+            }
+            if !keep {
+                return;
+            }
+        }
         self.write(&msg).await;
     }
 
+    /// Record the logs queued by `record_detached`, in the order they were recorded.
+    pub(crate) async fn record_pending(&mut self) {
+        for log in detached::take() {
+            self.record_in_span(
+                log.level,
+                &log.message,
+                log.position,
+                log.span,
+                Some(log.context),
+            )
+            .await;
+        }
+    }
+
     /// Record an info log.
-    pub async fn info(&mut self, message: &str, position: String) {
+    pub async fn info(&mut self, message: &str, position: Location) {
         self.record(LogLevel::Info, message, position).await;
     }
 
     /// Record a debug log.
-    pub async fn debug(&mut self, message: &str, position: String) {
+    pub async fn debug(&mut self, message: &str, position: Location) {
         self.record(LogLevel::Debug, message, position).await;
     }
 
     /// Record a warn log.
-    pub async fn warn(&mut self, message: &str, position: String) {
+    pub async fn warn(&mut self, message: &str, position: Location) {
         self.record(LogLevel::Warn, message, position).await;
     }
 
     /// Record an error log.
-    pub async fn error(&mut self, message: &str, position: String) {
+    pub async fn error(&mut self, message: &str, position: Location) {
         self.record(LogLevel::Error, message, position).await;
     }
 
     /// Record a trace log.
-    pub async fn trace(&mut self, message: &str, position: String) {
+    pub async fn trace(&mut self, message: &str, position: Location) {
         self.record(LogLevel::Trace, message, position).await;
     }
 
+    /// Record a fatal log.
+    pub async fn fatal(&mut self, message: &str, position: Location) {
+        self.record(LogLevel::Fatal, message, position).await;
+    }
+
+    /// Do the periodic work, called by the timer: summarize the run of repeats if the window has expired,
+    /// report the logs dropped by the rate limits if it is time to, write the buffered logs,
+    /// and sync them if the interval of the sync policy has passed.
+    pub(crate) async fn tick(&mut self) {
+        if let Some(window) = self.setting.dedup_window {
+            if let Some(summary) = self.dedup.expire(window) {
+                self.write(&summary).await;
+            }
+        }
+        for report in self.rate_limit_report() {
+            self.write(&report).await;
+        }
+        self.write_buffer().await;
+        if self.should_sync_by_interval() {
+            self.sync().await;
+        }
+    }
+
     /// Get the file object of the log file.
     async fn get_file(&self) -> File {
         let path = self.get_path(&self.current_file_prefix, self.current_index);
         // enable read and write and create a new file if not exist
-        File::options()
+        let file = File::options()
             .read(true)
             .write(true)
             .create_new(true)
             .open(path)
             .await
-            .expect("Cannot create the log file.")
+            .expect("Cannot create the log file.");
+        if self.setting.sync_policy != SyncPolicy::Never {
+            self.sync_dir().await;
+        }
+        file
+    }
+
+    /// Write the buffered logs into the current file.
+    pub(crate) async fn write_buffer(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+        // wait for the write to complete, so that the logs are in the file when the record returns
+        let file = self.file.as_mut().unwrap();
+        file.write_all(&self.buffer)
+            .await
+            .expect("Cannot write into the log file.");
+        file.flush().await.expect("Cannot write into the log file.");
+        self.buffer.clear();
+        self.buffered_since = None;
+    }
+
+    /// Shut down the logger: write the pending logs, the summary of the repeated logs, the shutdown log if enabled,
+    /// and the signature of the logs not signed yet, then flush and sync the current file.
+    pub async fn shutdown(&mut self) {
+        if !self.init {
+            return;
+        }
+        self.record_pending().await;
+        if let Some(summary) = self.dedup.flush() {
+            self.write(&summary).await;
+        }
+        if self.setting.shutdown_record {
+            self.info("logger shutdown", position!()).await;
+        }
+        if let Some(bytes) = self.close_file() {
+            self.buffer(bytes);
+        }
+        self.sync().await;
+    }
+
+    /// Write the buffered logs, and flush them into the current file.
+    pub async fn flush(&mut self) {
+        self.write_buffer().await;
+        if let Some(file) = self.file.as_mut() {
+            file.flush().await.expect("Cannot flush the log file.");
+        }
+    }
+
+    /// Flush the logs and sync the current file to the disk.
+    pub async fn sync(&mut self) {
+        self.flush().await;
+        if let Some(file) = self.file.as_mut() {
+            file.sync_data().await.expect("Cannot sync the log file.");
+        }
+        self.unsynced = 0;
+        self.last_sync = Instant::now();
+    }
+
+    /// Sync the log directory, so that the created files survive a crash. (not supported on every platform)
+    async fn sync_dir(&self) {
+        if let Ok(dir) = File::open(&self.setting.dir_path).await {
+            let _ = dir.sync_all().await;
+        }
     }
 
     /// Get the index of the current log file.
@@ -242,9 +810,9 @@ impl Logger {
     async fn get_index(&self, time_prefix: &str) -> usize {
         let mut count = 0;
         loop {
-            let path = self.get_path(time_prefix, count);
+            let [plain, encrypted] = self.get_paths(time_prefix, count);
             // if the file exists, then the index is the next one
-            if let Ok(_) = File::open(path).await {
+            if File::open(plain).await.is_ok() || File::open(encrypted).await.is_ok() {
                 count += 1
             } else {
                 return count;
@@ -258,40 +826,63 @@ impl Logger {
     /// Customize and initialize the log writer.
     pub(crate) fn init(&mut self, setting: Setting) {
         if self.init {
-            let position = position!().to_string();
+            let position = position!();
             self.warn("Log writer had been initialized!", position);
             return;
         }
 
         self.file = None;
         self.used_length = 0;
+        self.dedup = Dedup::default();
+        self.limiter = RateLimiter::default();
+        self.sampler = Sampler::default();
+        #[cfg(feature = "integrity")]
+        {
+            self.chain = None;
+            self.sequence = None;
+            self.block.clear();
+        }
+        self.buffer.clear();
+        self.buffered_since = None;
 
         self.setting = setting;
+        let errors = self.check_setting();
 
         self.init = true;
-        self.current_file_prefix = format!(
-            "{}",
-            chrono::Utc::now()
-                .with_timezone(&FixedOffset::east_opt(self.setting.time_zone * 3600).unwrap())
-                .format("%Y-%m-%d")
-        );
+        self.current_file_prefix = self.time_prefix();
         self.check_dir();
         self.current_index = self.get_index(&self.current_file_prefix);
+
+        for error in errors {
+            self.error(&error, position!());
+        }
     }
 
     /// clear the log directory.
     pub(crate) fn clear_dir(&mut self) {
-        fs::remove_dir_all(&self.setting.dir_path).expect("Cannot remove the dir.");
+        if fs::exists(&self.setting.dir_path).unwrap_or(false) {
+            fs::remove_dir_all(&self.setting.dir_path).expect("Cannot remove the dir.");
+        }
         fs::create_dir(&self.setting.dir_path).expect("Cannot create the dir.");
+        if self.setting.sync_policy != SyncPolicy::Never {
+            self.sync_dir();
+        }
         self.current_index = 0;
         self.used_length = 0;
         self.file = None;
-        self.current_file_prefix = format!(
-            "{}",
-            chrono::Utc::now()
-                .with_timezone(&FixedOffset::east_opt(self.setting.time_zone * 3600).unwrap())
-                .format(&self.setting.file_time_format)
-        );
+        #[cfg(feature = "integrity")]
+        {
+            self.chain = None;
+            self.sequence = None;
+            self.block.clear();
+        }
+        self.buffer.clear();
+        self.buffered_since = None;
+        #[cfg(feature = "encryption")]
+        if let Some(encryptor) = self.encryptor.as_mut() {
+            encryptor.reset();
+        }
+        self.current_file_prefix = self.time_prefix();
     }
 
     /// Write a single log message to the file.
@@ -300,20 +891,25 @@ impl Logger {
             return;
         }
 
-        if self.file.is_none() {
+        // no file is created if the logs cannot be written into it
+        let to_file = self.writes_files();
+        if to_file && self.file.is_none() {
             self.file = Some(self.get_file());
         }
 
-        for i in msg.split_enter() {
+        let mut written = false;
+        for i in msg.split_enter(self.setting.sanitize_messages) {
             // check if the time prefix has changed
             // (when a new day begins)
-            let time_prefix = format!(
-                "{}",
-                chrono::Utc::now()
-                    .with_timezone(&FixedOffset::east_opt(self.setting.time_zone * 3600).unwrap())
-                    .format("%Y-%m-%d")
-            );
-            if self.current_file_prefix != time_prefix {
+            let time_prefix = self.time_prefix();
+            if to_file && self.current_file_prefix != time_prefix {
+                if let Some(bytes) = self.close_file() {
+                    self.buffer(bytes);
+                }
+                self.write_buffer();
+                if self.setting.sync_policy != SyncPolicy::Never {
+                    self.sync();
+                }
                 self.current_file_prefix = time_prefix;
                 self.current_index = self.get_index(&self.current_file_prefix);
                 self.used_length = 0;
@@ -323,24 +919,46 @@ impl Logger {
             // check if should print to terminal.
             // requirement: print out is enabled and the level is high enough
             if self.setting.print_out
-                && self.setting.terminal_print_level.get_level() <= i.get_level()
+                && i.get_level() != LogLevel::Off
+                && i.get_level().is_at_least(self.setting.terminal_print_level)
             {
-                println!("{}", i.print())
+                println!("{}", i.print(&self.setting))
             };
 
             // check if should write to file.
-            // requirement: the level is high enough
-            if self.setting.file_record_level.get_level() <= i.get_level() {
-                self.file
-                    .as_mut()
-                    .unwrap()
-                    .write_all((i.print() + "\n").as_bytes())
-                    .expect("Cannot write into the log file.");
+            // requirement: the logs can be written into the files,
+            // and the level is high enough, or the level is always recorded
+            if to_file
+                && (i.get_level().is_always_recorded()
+                    || (i.get_level() != LogLevel::Off
+                        && i.get_level().is_at_least(self.setting.file_record_level)))
+            {
+                let bytes = self.file_line(&i);
+                written = true;
+                self.buffer(bytes);
                 self.used_length += 1;
             };
         }
 
+        // check if the buffered logs should be written, and synced to the disk
+        if written {
+            self.unsynced += 1;
+            if self.should_write_buffer(msg.get_level()) {
+                self.write_buffer();
+            }
+            if self.should_sync(msg.get_level()) {
+                self.sync();
+            }
+        }
+
         if self.setting.single_length != 0 && self.used_length >= self.setting.single_length {
+            if let Some(bytes) = self.close_file() {
+                self.buffer(bytes);
+            }
+            self.write_buffer();
+            if self.setting.sync_policy != SyncPolicy::Never {
+                self.sync();
+            }
             self.current_index += 1;
             self.used_length = 0;
             self.file = None;
@@ -348,53 +966,105 @@ impl Logger {
     }
 
     /// provide a method to log something by only a given string and [`LogLevel`].
-    pub fn record(&mut self, log_level: LogLevel, message: &str, position: String) {
+    pub fn record(&mut self, log_level: LogLevel, message: &str, position: Location) {
+        self.record_in_span(log_level, message, position, span::current_path());
+    }
+
+    /// Record a log inside the given span path, which is captured at the call site.
+    pub(crate) fn record_in_span(
+        &mut self,
+        log_level: LogLevel,
+        message: &str,
+        position: Location,
+        span: Option<String>,
+    ) {
         if !self.init {
             self.init = true
         }
-        let mut msg = LogMessage::new(
-            log_level,
-            message.to_string(),
-            self.setting.time_zone,
-            position,
-        );
-        msg.time.detailed_display = self.setting.time_detailed_display;
+        for report in self.rate_limit_report() {
+            self.write(&report);
+        }
+        if !self.rate_limit(log_level, position) {
+            return;
+        }
+        #[cfg(feature = "redaction")]
+        let mut message = self.redactor.redact(message).into_owned();
+        #[cfg(not(feature = "redaction"))]
+        let mut message = message.to_string();
+        if self.setting.sanitize_messages {
+            message = sanitize(&message);
+        }
+        let mut msg = self.new_message(log_level, message, position);
+        msg.span = span;
+
+        if let Some(window) = self.setting.dedup_window {
+            let (summary, keep) = self.dedup.check(&msg, window);
+            if let Some(summary) = summary {
+                self.write(&summary);
+            }
+            if !keep {
+                return;
+            }
+        }
         self.write(&msg);
     }
 
     /// Record an info log.
-    pub fn info(&mut self, message: &str, position: String) {
+    pub fn info(&mut self, message: &str, position: Location) {
         self.record(LogLevel::Info, message, position);
     }
 
     /// Record a debug log.
-    pub fn debug(&mut self, message: &str, position: String) {
+    pub fn debug(&mut self, message: &str, position: Location) {
         self.record(LogLevel::Debug, message, position);
     }
 
     /// Record a warn log.
-    pub fn warn(&mut self, message: &str, position: String) {
+    pub fn warn(&mut self, message: &str, position: Location) {
         self.record(LogLevel::Warn, message, position);
     }
 
     /// Record an error log.
-    pub fn error(&mut self, message: &str, position: String) {
+    pub fn error(&mut self, message: &str, position: Location) {
         self.record(LogLevel::Error, message, position);
     }
 
     /// Record a trace log.
-    pub fn trace(&mut self, message: &str, position: String) {
+    pub fn trace(&mut self, message: &str, position: Location) {
         self.record(LogLevel::Trace, message, position);
     }
 
+    /// Record a fatal log.
+    pub fn fatal(&mut self, message: &str, position: Location) {
+        self.record(LogLevel::Fatal, message, position);
+    }
+
+    /// Do the periodic work, called by the timer: summarize the run of repeats if the window has expired,
+    /// report the logs dropped by the rate limits if it is time to, write the buffered logs,
+    /// and sync them if the interval of the sync policy has passed.
+    pub(crate) fn tick(&mut self) {
+        if let Some(window) = self.setting.dedup_window {
+            if let Some(summary) = self.dedup.expire(window) {
+                self.write(&summary);
+            }
+        }
+        for report in self.rate_limit_report() {
+            self.write(&report);
+        }
+        self.write_buffer();
+        if self.should_sync_by_interval() {
+            self.sync();
+        }
+    }
+
     /// Get the index of the current log file.
     /// This is used when resume the logging, since have to keep a continuos order of the log files.
     fn get_index(&self, time_prefix: &str) -> usize {
         let mut count = 0;
         loop {
-            let path = self.get_path(time_prefix, count);
+            let paths = self.get_paths(time_prefix, count);
             // if the file exists, then the index is the next one
-            if let Ok(_) = File::open(path) {
+            if paths.iter().any(|path| File::open(path).is_ok()) {
                 count += 1
             } else {
                 return count;
@@ -406,12 +1076,73 @@ impl Logger {
     fn get_file(&self) -> File {
         let path = self.get_path(&self.current_file_prefix, self.current_index);
         // enable read and write and create a new file if not exist
-        File::options()
+        let file = File::options()
             .read(true)
             .write(true)
             .create_new(true)
             .open(path)
-            .expect("Cannot create the log file.")
+            .expect("Cannot create the log file.");
+        if self.setting.sync_policy != SyncPolicy::Never {
+            self.sync_dir();
+        }
+        file
+    }
+
+    /// Write the buffered logs into the current file.
+    pub(crate) fn write_buffer(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+        self.file
+            .as_mut()
+            .unwrap()
+            .write_all(&self.buffer)
+            .expect("Cannot write into the log file.");
+        self.buffer.clear();
+        self.buffered_since = None;
+    }
+
+    /// Shut down the logger: write the summary of the repeated logs, the shutdown log if enabled,
+    /// and the signature of the logs not signed yet, then flush and sync the current file.
+    pub fn shutdown(&mut self) {
+        if !self.init {
+            return;
+        }
+        if let Some(summary) = self.dedup.flush() {
+            self.write(&summary);
+        }
+        if self.setting.shutdown_record {
+            self.info("logger shutdown", position!());
+        }
+        if let Some(bytes) = self.close_file() {
+            self.buffer(bytes);
+        }
+        self.sync();
+    }
+
+    /// Write the buffered logs, and flush them into the current file.
+    pub fn flush(&mut self) {
+        self.write_buffer();
+        if let Some(file) = self.file.as_mut() {
+            file.flush().expect("Cannot flush the log file.");
+        }
+    }
+
+    /// Flush the logs and sync the current file to the disk.
+    pub fn sync(&mut self) {
+        self.flush();
+        if let Some(file) = self.file.as_mut() {
+            file.sync_data().expect("Cannot sync the log file.");
+        }
+        self.unsynced = 0;
+        self.last_sync = Instant::now();
+    }
+
+    /// Sync the log directory, so that the created files survive a crash. (not supported on every platform)
+    fn sync_dir(&self) {
+        if let Ok(dir) = File::open(&self.setting.dir_path) {
+            let _ = dir.sync_all();
+        }
     }
 }
 
@@ -1,4 +1,9 @@
-use super::{time::Time, LogLevel};
+use super::{
+    context::Context,
+    location::remap_file,
+    time::{Elapsed, Time, TimeZone},
+    Location, LogLevel, Setting,
+};
 
 #[derive(Clone, Debug)]
 pub(crate) struct LogMessage {
@@ -9,47 +14,115 @@ pub(crate) struct LogMessage {
     // time of the log
     pub(crate) time: Time,
     // position
-    position: String,
+    position: Location,
+    // where the log is emitted from
+    pub(crate) context: Context,
+    // monotonic time of the log
+    pub(crate) elapsed: Elapsed,
+    // the path of the spans the log is emitted in
+    pub(crate) span: Option<String>,
 }
 
 impl LogMessage {
     /// Creates a new log message
-    pub fn new(level: LogLevel, message: String, time_zone: i32, position: String) -> Self {
+    pub fn new(level: LogLevel, message: String, time_zone: &TimeZone, position: Location) -> Self {
         Self {
             level,
             message,
             position,
             time: Time::now(time_zone),
+            context: Context::default(),
+            elapsed: Elapsed::default(),
+            span: None,
         }
     }
 
     /// Formatting the log message
-    pub fn print(&self) -> String {
-        format!(
-            "{} {} [{}] {}",
-            self.time, self.level, self.position, self.message
-        )
+    pub fn print(&self, setting: &Setting) -> String {
+        let position = self.position.format(
+            setting.position_format,
+            &remap_file(self.position.file, setting),
+        );
+        let message = match &self.span {
+            Some(span) => format!("{}: {}", span, self.message),
+            None => self.message.clone(),
+        };
+        let mut time = self.time.format(&setting.time_format);
+        if !self.elapsed.is_empty() {
+            time = format!("{} [{}]", time, self.elapsed);
+        }
+        if self.context.is_empty() {
+            format!("{} {} [{}] {}", time, self.level, position, message)
+        } else {
+            format!(
+                "{} {} ({}) [{}] {}",
+                time, self.level, self.context, position, message
+            )
+        }
     }
 
     /// Get the level of the log
-    pub fn get_level(&self) -> usize {
-        self.level as usize
+    pub fn get_level(&self) -> LogLevel {
+        self.level
+    }
+
+    /// Check whether the log repeats the other one. (the same level, position, span and message)
+    pub fn is_repeat_of(&self, other: &Self) -> bool {
+        self.level == other.level
+            && self.position == other.position
+            && self.span == other.span
+            && self.message == other.message
+    }
+
+    /// Get a copy of the log with another message.
+    pub fn with_message(&self, message: String) -> Self {
+        Self {
+            message,
+            ..self.clone()
+        }
     }
 
     /// Deal with the log with multiline.
     /// Convert multiline log into multiple single line log.
-    pub fn split_enter(&self) -> Vec<Self> {
+    /// The continuation lines are marked by [`CONTINUATION`] if `mark_continuation` is set.
+    pub fn split_enter(&self, mark_continuation: bool) -> Vec<Self> {
         let mut messages = Vec::new();
-        for line in self.message.lines() {
-            messages.push(Self {
-                position: self.position.clone(),
-                level: self.level.clone(),
-                message: line.to_string(),
-                time: self.time.clone(),
-            });
+        for (index, line) in self.message.lines().enumerate() {
+            if mark_continuation && index > 0 {
+                messages.push(self.with_message(format!("{}{}", CONTINUATION, line)));
+            } else {
+                messages.push(self.with_message(line.to_string()));
+            }
         }
         messages
     }
 }
 
+/// The marker of the continuation lines of a multiline log, when the messages are sanitized.
+pub(crate) const CONTINUATION: &str = "| ";
+
+/// Escape the characters that can forge a log or control the terminal: CR, ESC and the other C0 and C1 controls,
+/// the bidi overrides and the Unicode line separators.
+/// Newlines and tabs are kept, the newlines are handled by [`LogMessage::split_enter`].
+pub(crate) fn sanitize(message: &str) -> String {
+    let mut sanitized = String::with_capacity(message.len());
+    for c in message.chars() {
+        match c {
+            '\n' | '\t' => sanitized.push(c),
+            '\r' => sanitized.push_str("\\r"),
+            '\x00'..='\x1f' | '\x7f' => sanitized.push_str(&format!("\\x{:02x}", c as u32)),
+            '\u{80}'..='\u{9f}'
+            | '\u{61c}'
+            | '\u{200e}'
+            | '\u{200f}'
+            | '\u{2028}'
+            | '\u{2029}'
+            | '\u{202a}'..='\u{202e}'
+            | '\u{2066}'..='\u{2069}' => sanitized.push_str(&format!("\\u{{{:x}}}", c as u32)),
+            _ => sanitized.push(c),
+        }
+    }
+    sanitized
+}
+
 unsafe impl Send for LogMessage {}
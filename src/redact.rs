@@ -0,0 +1,235 @@
+use super::SecretKey;
+use hmac::{Hmac, Mac};
+use regex::{Captures, Regex};
+use sha2::Sha256;
+use std::borrow::Cow;
+
+/// What is matched by a [`RedactRule`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum RedactTarget {
+    /// a regular expression. If it has a group named `secret`, only the group is replaced.
+    Pattern(String),
+    /// the value of a field with the name (case-insensitive), such as `password=...` or `"password": "..."`.
+    /// The fields whose names end with it, such as `db_password`, are matched as well.
+    Field(String),
+    /// the credit card numbers passing the Luhn check, with or without spaces or dashes between the digits.
+    CreditCard,
+}
+
+/// How the matched secret is replaced.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum Redaction {
+    /// replace with `***`.
+    #[default]
+    Mask,
+    /// replace with a stable hash keyed by [`redact_key`](crate::Setting::redact_key), such as `hmac:3f1a9c0e5b7d2a64`,
+    /// so the same secret can still be correlated, but cannot be guessed from the hash without the key.
+    Hash,
+}
+
+/// A rule to redact the secrets in the logs before they are written.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RedactRule {
+    /// what is matched.
+    pub target: RedactTarget,
+    /// how the match is replaced.
+    pub redaction: Redaction,
+}
+
+impl RedactRule {
+    /// Redact the matches of the regular expression.
+    pub fn pattern(pattern: impl Into<String>) -> Self {
+        Self {
+            target: RedactTarget::Pattern(pattern.into()),
+            redaction: Redaction::Mask,
+        }
+    }
+
+    /// Redact the value of the field with the name, or a name ending with it.
+    pub fn field(name: impl Into<String>) -> Self {
+        Self {
+            target: RedactTarget::Field(name.into()),
+            redaction: Redaction::Mask,
+        }
+    }
+
+    /// Replace the matches with a stable keyed hash instead of `***`.
+    pub fn hashed(mut self) -> Self {
+        self.redaction = Redaction::Hash;
+        self
+    }
+
+    /// Redact the bearer tokens, such as `Authorization: Bearer eyJhbGci...`.
+    pub fn bearer_token() -> Self {
+        Self::pattern(r"(?i)\bbearer\s+(?P<secret>[A-Za-z0-9\-._~+/]+=*)")
+    }
+
+    /// Redact the email addresses.
+    pub fn email() -> Self {
+        Self::pattern(r"\b[A-Za-z0-9._%+\-]+@[A-Za-z0-9.\-]+\.[A-Za-z]{2,}\b")
+    }
+
+    /// Redact the credit card numbers, with or without spaces or dashes between the digits.
+    /// Only the numbers passing the Luhn check are redacted, so the other long numbers, such as ids, are kept.
+    pub fn credit_card() -> Self {
+        Self {
+            target: RedactTarget::CreditCard,
+            redaction: Redaction::Mask,
+        }
+    }
+
+    /// Redact the AWS access key ids.
+    pub fn aws_access_key() -> Self {
+        Self::pattern(r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b")
+    }
+
+    /// The built-in rules: bearer tokens, emails, credit card numbers, AWS access keys,
+    /// and the fields named `password`, `secret` and `token`.
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            Self::bearer_token(),
+            Self::email(),
+            Self::credit_card(),
+            Self::aws_access_key(),
+            Self::field("password"),
+            Self::field("secret"),
+            Self::field("token"),
+        ]
+    }
+
+    /// Get the regular expression of the rule.
+    fn regex(&self) -> Result<Regex, regex::Error> {
+        match &self.target {
+            RedactTarget::Pattern(pattern) => Regex::new(pattern),
+            RedactTarget::CreditCard => Regex::new(r"\b(?:\d[ \-]?){12,18}\d\b"),
+            RedactTarget::Field(name) => Regex::new(&format!(
+                r#"(?i)\b[\w\-]*?{}"?\s*[=:]\s*(?P<secret>"[^"]*"|[^\s,;&}}]+)"#,
+                regex::escape(name)
+            )),
+        }
+    }
+
+    /// Get the check a match has to pass to be redacted, if any.
+    fn check(&self) -> Option<fn(&str) -> bool> {
+        match self.target {
+            RedactTarget::CreditCard => Some(luhn),
+            _ => None,
+        }
+    }
+}
+
+/// Check the digits of the number by the Luhn algorithm, ignoring the separators.
+fn luhn(number: &str) -> bool {
+    let sum: u32 = number
+        .chars()
+        .rev()
+        .filter_map(|c| c.to_digit(10))
+        .enumerate()
+        .map(|(i, digit)| match (i % 2, digit * 2) {
+            (0, _) => digit,
+            (_, doubled) if doubled > 9 => doubled - 9,
+            (_, doubled) => doubled,
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+impl Redaction {
+    /// Get the replacement of the secret, hashed with the key.
+    fn replace(&self, secret: &str, key: &[u8]) -> String {
+        match self {
+            Redaction::Mask => "***".to_string(),
+            Redaction::Hash => {
+                let mut mac =
+                    Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+                mac.update(secret.as_bytes());
+                let digest = mac.finalize().into_bytes();
+                let hex: String = digest[..8].iter().map(|b| format!("{:02x}", b)).collect();
+                format!("hmac:{}", hex)
+            }
+        }
+    }
+}
+
+/// A redact rule compiled by the logger.
+#[derive(Debug)]
+struct Compiled {
+    /// the regular expression of the target.
+    regex: Regex,
+    /// the check a match has to pass to be redacted, if any.
+    check: Option<fn(&str) -> bool>,
+    /// how the match is replaced.
+    redaction: Redaction,
+}
+
+/// The compiled redact rules of the logger.
+#[derive(Debug, Default)]
+pub(crate) struct Redactor {
+    rules: Vec<Compiled>,
+    /// the key of the hashed rules.
+    key: Vec<u8>,
+}
+
+impl Redactor {
+    /// Compile the rules with the key of the hashed rules, and get the errors of the invalid ones, which are ignored.
+    /// Without a key, the hashed rules are masked instead.
+    pub(crate) fn compile(rules: &[RedactRule], key: Option<&SecretKey>) -> (Self, Vec<String>) {
+        let mut redactor = Self::default();
+        let mut errors = Vec::new();
+        let key = key.filter(|key| !key.is_empty());
+        if key.is_none() && rules.iter().any(|rule| rule.redaction == Redaction::Hash) {
+            errors.push(
+                "The redact key is not set, the hashed rules are masked instead.".to_string(),
+            );
+        }
+        redactor.key = key.map(|key| key.as_bytes().to_vec()).unwrap_or_default();
+        for rule in rules {
+            match rule.regex() {
+                Ok(regex) => redactor.rules.push(Compiled {
+                    regex,
+                    check: rule.check(),
+                    redaction: if key.is_some() {
+                        rule.redaction
+                    } else {
+                        Redaction::Mask
+                    },
+                }),
+                Err(error) => errors.push(format!(
+                    "Invalid redact rule {:?}: {}, the rule is ignored.",
+                    rule.target, error
+                )),
+            }
+        }
+        (redactor, errors)
+    }
+
+    /// Redact the message by all the rules in order.
+    pub(crate) fn redact<'a>(&self, message: &'a str) -> Cow<'a, str> {
+        let mut message = Cow::Borrowed(message);
+        for Compiled {
+            regex,
+            check,
+            redaction,
+        } in &self.rules
+        {
+            if let Cow::Owned(redacted) = regex.replace_all(&message, |captures: &Captures| {
+                let whole = captures.get(0).expect("the whole match");
+                if check.is_some_and(|check| !check(whole.as_str())) {
+                    return whole.as_str().to_string();
+                }
+                match captures.name("secret") {
+                    Some(secret) => format!(
+                        "{}{}{}",
+                        &whole.as_str()[..secret.start() - whole.start()],
+                        redaction.replace(secret.as_str(), &self.key),
+                        &whole.as_str()[secret.end() - whole.start()..]
+                    ),
+                    None => redaction.replace(whole.as_str(), &self.key),
+                }
+            }) {
+                message = Cow::Owned(redacted);
+            }
+        }
+        message
+    }
+}
@@ -0,0 +1,114 @@
+use super::LogLevel;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// How many logs are kept by a [`SampleRule`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Sample {
+    /// keep each log with the probability. (`0.01` keeps 1% of the logs)
+    Probability(f64),
+    /// keep the first log of every `n` logs.
+    EveryNth(u64),
+}
+
+/// A rule to keep only a sample of the logs of a level, optionally only from a module.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SampleRule {
+    /// the level of the logs to sample.
+    pub level: LogLevel,
+    /// the module (and its submodules) of the logs to sample. (`None` as all modules)
+    pub module: Option<String>,
+    /// how many logs are kept.
+    pub sample: Sample,
+}
+
+impl SampleRule {
+    /// Keep each log of the level with the probability.
+    pub fn probability(level: LogLevel, probability: f64) -> Self {
+        Self {
+            level,
+            module: None,
+            sample: Sample::Probability(probability),
+        }
+    }
+
+    /// Keep one of every `n` logs of the level.
+    pub fn every_nth(level: LogLevel, n: u64) -> Self {
+        Self {
+            level,
+            module: None,
+            sample: Sample::EveryNth(n),
+        }
+    }
+
+    /// Apply the rule only to the logs from the module and its submodules.
+    pub fn module(mut self, module: &str) -> Self {
+        self.module = Some(module.to_string());
+        self
+    }
+
+    /// Check whether the rule applies to the log.
+    fn matches(&self, level: LogLevel, module_path: &str) -> bool {
+        self.level == level
+            && self.module.as_ref().is_none_or(|module| {
+                module_path
+                    .strip_prefix(module.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+            })
+    }
+}
+
+/// The state of the sample rules.
+#[derive(Debug)]
+pub(crate) struct Sampler {
+    /// the count of the logs matched by each rule.
+    counts: Vec<u64>,
+    /// the state of the random number generator. (xorshift)
+    random: u64,
+}
+
+impl Default for Sampler {
+    fn default() -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|time| time.as_nanos() as u64)
+            .unwrap_or_default();
+        Self {
+            counts: Vec::new(),
+            random: seed | 1,
+        }
+    }
+}
+
+impl Sampler {
+    /// Check whether the log is kept by the first rule that applies to it.
+    pub(crate) fn check(
+        &mut self,
+        rules: &[SampleRule],
+        level: LogLevel,
+        module_path: &str,
+    ) -> bool {
+        let Some(index) = rules
+            .iter()
+            .position(|rule| rule.matches(level, module_path))
+        else {
+            return true;
+        };
+        match rules[index].sample {
+            Sample::Probability(probability) => self.next_f64() < probability,
+            Sample::EveryNth(n) => {
+                self.counts.resize(rules.len(), 0);
+                let count = self.counts[index];
+                self.counts[index] += 1;
+                count.is_multiple_of(n.max(1))
+            }
+        }
+    }
+
+    /// Generate a random number in `[0, 1)`.
+    fn next_f64(&mut self) -> f64 {
+        self.random ^= self.random << 13;
+        self.random ^= self.random >> 7;
+        self.random ^= self.random << 17;
+        (self.random >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
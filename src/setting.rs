@@ -1,4 +1,26 @@
-use super::LogLevel;
+#[cfg(feature = "redaction")]
+use super::RedactRule;
+#[cfg(any(feature = "encryption", feature = "integrity", feature = "redaction"))]
+use super::SecretKey;
+use super::{LogLevel, PositionFormat, RateLimit, SampleRule, TimeFormat, TimeZone};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// When the logs written into the files are synced to the disk, so that they survive a crash or a power loss.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum SyncPolicy {
+    /// leave it to the operating system.
+    #[default]
+    Never,
+    /// sync after every `n` logs.
+    EveryRecords(usize),
+    /// sync after a log when the interval has passed since the last sync, or by a timer if no log comes after.
+    Interval(Duration),
+    /// sync after each log of the level or higher, such as [`LogLevel::Error`].
+    Level(LogLevel),
+    /// sync after each log.
+    Always,
+}
 
 /// the configuration of the logger.
 #[derive(Debug)]
@@ -11,16 +33,87 @@ pub struct Setting {
     pub file_record_level: LogLevel,
     /// define the minimum [`LogLevel`] of the log that should be printed. (inclusive)
     pub terminal_print_level: LogLevel,
-    /// define to show the detailed time or not.
+    /// define to show the detailed time or not. (only for [`TimeFormat::Standard`])
     pub time_detailed_display: bool,
+    /// the format of the time in each log message, separated from the format of the log files' name.
+    pub time_format: TimeFormat,
+    /// setting whether to record the monotonic time since the logger started in each log.
+    pub record_elapsed: bool,
+    /// setting whether to record the monotonic time since the previous log on the same thread in each log.
+    pub record_delta: bool,
     /// the prefix of the time.
     pub file_time_format: String,
     /// the time zone of the log.
-    pub time_zone: i32,
+    pub time_zone: TimeZone,
     /// setting whether to print the log to the terminal.
     pub print_out: bool,
     /// setting whether to log or not
     pub disabled: bool,
+    /// the window to suppress the consecutive repeated logs. (`None` as disabled)
+    /// a summary log with the count of the repeats is written when the run of repeats ends or the window expires (checked by a timer).
+    pub dedup_window: Option<Duration>,
+    /// the rate limit of each call site, defined by the [`LogLevel`] of the log.
+    pub rate_limits: HashMap<LogLevel, RateLimit>,
+    /// the rate limit shared by all logs. (`None` as unlimited)
+    pub global_rate_limit: Option<RateLimit>,
+    /// the interval to report the count of logs dropped by the rate limits of each position (checked by a timer).
+    pub rate_limit_report_interval: Duration,
+    /// the rules to keep only a sample of the logs. (the first rule that applies to a log is used)
+    /// the sampling is decided before the message is formatted.
+    pub sampling: Vec<SampleRule>,
+    /// setting whether to record the name of the thread in each log.
+    pub record_thread_name: bool,
+    /// setting whether to record the id of the thread in each log.
+    pub record_thread_id: bool,
+    /// setting whether to record the id of the process in each log.
+    pub record_process_id: bool,
+    /// setting whether to record the name of the host in each log.
+    pub record_hostname: bool,
+    /// setting whether to record the id of the tokio task in each log. (only with the `async` feature)
+    pub record_task_id: bool,
+    /// define how much of the position (module path, function path, file, line and column) is shown.
+    pub position_format: PositionFormat,
+    /// the prefixes of the file paths in the position to replace, with their replacements. (the first matching one is used)
+    pub path_remap: Vec<(String, String)>,
+    /// the prefix to strip from the file paths in the position. (only when it ends at a separator)
+    pub path_strip_prefix: Option<String>,
+    /// setting whether to use `/` as the separator of the file paths in the position.
+    pub normalize_path_separators: bool,
+    /// the rules to redact the secrets in the messages before any output, applied in order.
+    /// an invalid rule is reported by an error log at init, and ignored.
+    #[cfg(feature = "redaction")]
+    pub redact_rules: Vec<RedactRule>,
+    /// the HMAC key of the hashed redact rules. (`None` as the hashed rules are masked instead)
+    #[cfg(feature = "redaction")]
+    pub redact_key: Option<SecretKey>,
+    /// setting whether to escape the control characters, ANSI sequences and bidi overrides in the messages,
+    /// and mark the continuation lines of the multiline messages with `| `, so that a message cannot forge a log.
+    pub sanitize_messages: bool,
+    /// setting whether to append to each line in the files a hash of the previous hash and the line,
+    /// and begin each file with a header linking to the final hash of the previous file. (see [`verify`](crate::verify))
+    #[cfg(feature = "integrity")]
+    pub hash_chain: bool,
+    /// the HMAC key to sign the logs in the files. (`None` as not signed, see [`verify_signatures`](crate::verify_signatures))
+    /// a sequence number is appended to each log, and a signature line follows each block of logs.
+    #[cfg(feature = "integrity")]
+    pub signing_key: Option<SecretKey>,
+    /// the number of logs signed by each signature, the remaining logs are signed when the file is rotated.
+    #[cfg(feature = "integrity")]
+    pub signing_block: usize,
+    /// the 32 bytes key to encrypt the log files with ChaCha20-Poly1305, which are written as `.log.enc`. (`None` as not encrypted)
+    /// an invalid key is reported on the standard error at init, and no file is created or written. (see [`decrypt_file`](crate::decrypt_file))
+    #[cfg(feature = "encryption")]
+    pub encryption_key: Option<SecretKey>,
+    /// when the logs are synced to the disk. the file is also synced before rotation,
+    /// and the directory is synced when a file is created, unless it is [`SyncPolicy::Never`].
+    pub sync_policy: SyncPolicy,
+    /// the size in bytes of the buffer of the logs to write into the files. (0 as unbuffered)
+    /// the buffer is written when it is full, on rotation, on [`flush`](crate::flush), and immediately for the errors.
+    pub buffer_size: usize,
+    /// the max time a log stays in the buffer, the buffer is written by a timer when it passes.
+    pub buffer_latency: Duration,
+    /// whether to record an info log "logger shutdown" when the logger is shut down.
+    pub shutdown_record: bool,
 }
 
 impl std::default::Default for Setting {
@@ -38,10 +131,44 @@ impl std::default::Default for Setting {
             file_record_level: LogLevel::Trace,
             terminal_print_level,
             time_detailed_display: false,
+            time_format: TimeFormat::default(),
+            record_elapsed: false,
+            record_delta: false,
             file_time_format: "%Y-%m-%d".to_string(),
-            time_zone: 0,
+            time_zone: TimeZone::default(),
             print_out: false,
             disabled: false,
+            dedup_window: None,
+            rate_limits: HashMap::new(),
+            global_rate_limit: None,
+            rate_limit_report_interval: Duration::from_secs(60),
+            sampling: Vec::new(),
+            record_thread_name: false,
+            record_thread_id: false,
+            record_process_id: false,
+            record_hostname: false,
+            record_task_id: false,
+            position_format: PositionFormat::Short,
+            path_remap: Vec::new(),
+            path_strip_prefix: None,
+            normalize_path_separators: false,
+            #[cfg(feature = "redaction")]
+            redact_rules: Vec::new(),
+            #[cfg(feature = "redaction")]
+            redact_key: None,
+            sanitize_messages: false,
+            #[cfg(feature = "integrity")]
+            hash_chain: false,
+            #[cfg(feature = "integrity")]
+            signing_key: None,
+            #[cfg(feature = "integrity")]
+            signing_block: 1,
+            #[cfg(feature = "encryption")]
+            encryption_key: None,
+            sync_policy: SyncPolicy::Never,
+            buffer_size: 0,
+            buffer_latency: Duration::from_secs(1),
+            shutdown_record: false,
         }
     }
 }
@@ -0,0 +1,228 @@
+use super::chain::{self, encrypted_file, log_files, read_written, written_files};
+use super::Setting;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// The beginning of the line with the signature of a block of logs.
+pub(crate) const SIGNATURE: &str = "#sig ";
+
+/// The separator of the sequence number appended to each signed log.
+pub(crate) const SEQUENCE: &str = " ~";
+
+/// Get the HMAC-SHA256 of the block of logs with the sequence numbers from `first` to `last`, in hex.
+pub(crate) fn sign(key: &[u8], first: u64, last: u64, lines: &[String]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(format!("{}-{}", first, last).as_bytes());
+    for line in lines {
+        mac.update(b"\n");
+        mac.update(line.as_bytes());
+    }
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Remove the hash of the chain from the line, if the file is hash-chained.
+fn strip_hash(line: &str, chained: bool) -> &str {
+    match line.rsplit_once(" #") {
+        Some((content, _)) if chained => content,
+        _ => line,
+    }
+}
+
+/// Get the sequence number of the next log, to continue the sequence after a restart.
+pub(crate) fn next_sequence(setting: &Setting) -> u64 {
+    for file in written_files(setting).iter().rev() {
+        let content = read_written(file, setting);
+        let chained = content.starts_with(chain::HEADER);
+        for line in content.lines().rev() {
+            let line = strip_hash(line, chained);
+            if let Some(sequence) = line
+                .rsplit_once(SEQUENCE)
+                .and_then(|(_, sequence)| sequence.parse::<u64>().ok())
+            {
+                return sequence + 1;
+            }
+        }
+    }
+    0
+}
+
+/// A problem found by [`verify_signatures`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum SignatureIssue {
+    /// the log is not signed, because it was written without the key or after the last signature.
+    Unsigned { file: PathBuf, line: usize },
+    /// the block of logs ending at the signature line does not match the signature.
+    Altered { file: PathBuf, line: usize },
+    /// the logs with the sequence numbers from `first` to `last` are missing before the line.
+    Missing {
+        file: PathBuf,
+        line: usize,
+        first: u64,
+        last: u64,
+    },
+    /// the log has a sequence number lower than the previous one.
+    Reordered {
+        file: PathBuf,
+        line: usize,
+        sequence: u64,
+    },
+}
+
+impl fmt::Display for SignatureIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignatureIssue::Unsigned { file, line } => {
+                write!(f, "{}:{}: the log is not signed", file.display(), line)
+            }
+            SignatureIssue::Altered { file, line } => write!(
+                f,
+                "{}:{}: the block of logs does not match the signature",
+                file.display(),
+                line
+            ),
+            SignatureIssue::Missing {
+                file,
+                line,
+                first,
+                last,
+            } => write!(
+                f,
+                "{}:{}: the logs from {} to {} are missing",
+                file.display(),
+                line,
+                first,
+                last
+            ),
+            SignatureIssue::Reordered {
+                file,
+                line,
+                sequence,
+            } => write!(
+                f,
+                "{}:{}: the log {} is out of order",
+                file.display(),
+                line,
+                sequence
+            ),
+        }
+    }
+}
+
+/// The result of [`verify_signatures`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SignatureReport {
+    /// the number of the logs with valid signatures.
+    pub verified: usize,
+    /// the problems found, in the order of the files and lines.
+    pub issues: Vec<SignatureIssue>,
+}
+
+impl SignatureReport {
+    /// Check whether no problem is found.
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Verify the signatures of the log files in the directory, written with the `signing_key`.
+/// The files are read in the order they were written, and the missing files show up as missing logs.
+/// A directory without any log file, or with an encrypted one, is an error, so that it does not pass unchecked.
+pub fn verify_signatures(dir: impl AsRef<Path>, key: &[u8]) -> std::io::Result<SignatureReport> {
+    let mut report = SignatureReport::default();
+    let mut expected: Option<u64> = None;
+    // the logs after the last signature: (file, line, sequence number, text)
+    let mut block: Vec<(PathBuf, usize, u64, String)> = Vec::new();
+
+    if let Some(file) = encrypted_file(dir.as_ref()) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!(
+                "{} is encrypted, decrypt it before verifying",
+                file.display()
+            ),
+        ));
+    }
+    let files = log_files(dir.as_ref(), ".log")?;
+    if files.is_empty() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "no log file is found",
+        ));
+    }
+    for file in files {
+        let content = std::fs::read_to_string(&file)?;
+        let chained = content.starts_with(chain::HEADER);
+        for (index, line) in content.lines().enumerate() {
+            let number = index + 1;
+            if line.starts_with(chain::HEADER) {
+                continue;
+            }
+            let line = strip_hash(line, chained);
+
+            if let Some(signature) = line.strip_prefix(SIGNATURE) {
+                let valid = match (block.first(), block.last()) {
+                    (Some(first), Some(last)) => {
+                        let lines: Vec<String> =
+                            block.iter().map(|(_, _, _, text)| text.clone()).collect();
+                        let range = format!("{}-{} ", first.2, last.2);
+                        signature.strip_prefix(&range)
+                            == Some(sign(key, first.2, last.2, &lines).as_str())
+                    }
+                    _ => false,
+                };
+                if valid {
+                    report.verified += block.len();
+                } else {
+                    report.issues.push(SignatureIssue::Altered {
+                        file: file.clone(),
+                        line: number,
+                    });
+                }
+                block.clear();
+                continue;
+            }
+
+            let Some(sequence) = line
+                .rsplit_once(SEQUENCE)
+                .and_then(|(_, sequence)| sequence.parse::<u64>().ok())
+            else {
+                report.issues.push(SignatureIssue::Unsigned {
+                    file: file.clone(),
+                    line: number,
+                });
+                continue;
+            };
+            match expected {
+                Some(expected) if sequence > expected => {
+                    report.issues.push(SignatureIssue::Missing {
+                        file: file.clone(),
+                        line: number,
+                        first: expected,
+                        last: sequence - 1,
+                    })
+                }
+                Some(expected) if sequence < expected => {
+                    report.issues.push(SignatureIssue::Reordered {
+                        file: file.clone(),
+                        line: number,
+                        sequence,
+                    })
+                }
+                _ => {}
+            }
+            expected = Some(expected.map_or(sequence + 1, |expected| expected.max(sequence + 1)));
+            block.push((file.clone(), number, sequence, line.to_string()));
+        }
+    }
+
+    for (file, line, _, _) in block {
+        report.issues.push(SignatureIssue::Unsigned { file, line });
+    }
+    Ok(report)
+}
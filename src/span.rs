@@ -0,0 +1,165 @@
+use super::{record_detached, Location, LogLevel};
+#[cfg(feature = "async")]
+use lazy_static::lazy_static;
+use std::cell::RefCell;
+#[cfg(feature = "async")]
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+
+/// The id of the next span, used to find the span in the stack when it exits.
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    /// The stack of the entered spans on this thread.
+    static STACK: RefCell<Vec<(u64, String)>> = const { RefCell::new(Vec::new()) };
+}
+
+#[cfg(feature = "async")]
+lazy_static! {
+    /// The stacks of the entered spans of each tokio task.
+    static ref TASK_STACKS: std::sync::Mutex<HashMap<tokio::task::Id, Vec<(u64, String)>>> =
+        std::sync::Mutex::new(HashMap::new());
+}
+
+/// Access the stack of the current task, or the current thread outside a task.
+fn with_stack<R>(f: impl FnOnce(&mut Vec<(u64, String)>) -> R) -> R {
+    #[cfg(feature = "async")]
+    if let Some(id) = tokio::task::try_id() {
+        let mut stacks = TASK_STACKS
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let stack = stacks.entry(id).or_default();
+        let result = f(stack);
+        if stack.is_empty() {
+            stacks.remove(&id);
+        }
+        return result;
+    }
+    STACK.with(|stack| f(&mut stack.borrow_mut()))
+}
+
+/// Get the path of the spans entered on the current thread (or task), such as `handle_request > db_query`.
+pub(crate) fn current_path() -> Option<String> {
+    with_stack(|stack| {
+        (!stack.is_empty()).then(|| {
+            stack
+                .iter()
+                .map(|(_, name)| name.as_str())
+                .collect::<Vec<_>>()
+                .join(" > ")
+        })
+    })
+}
+
+/// A scope of work, which logs when it is entered and exited (with the duration),
+/// and prefixes the logs emitted inside it with the path of the spans.
+/// It is usually created by [`span!`](crate::span), and exits when it is dropped.
+#[derive(Debug)]
+pub struct Span {
+    /// the id of the span.
+    id: u64,
+    /// the level of the enter and exit logs.
+    level: LogLevel,
+    /// the name of the span.
+    name: String,
+    /// the fields of the span, formatted as `key=value`.
+    fields: Vec<String>,
+    /// where the span is created.
+    position: Location,
+    /// when the span is entered.
+    started: Instant,
+    /// whether the span has been entered and not exited yet.
+    entered: bool,
+}
+
+impl Span {
+    /// Create a span without entering it.
+    pub fn new(level: LogLevel, name: &str, fields: Vec<String>, position: Location) -> Self {
+        Self {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            level,
+            name: name.to_string(),
+            fields,
+            position,
+            started: Instant::now(),
+            entered: false,
+        }
+    }
+
+    /// Get the name with the fields, such as `handle_request{id=7}`.
+    fn full_name(&self) -> String {
+        if self.fields.is_empty() {
+            self.name.clone()
+        } else {
+            format!("{}{{{}}}", self.name, self.fields.join(" "))
+        }
+    }
+
+    /// Push the span onto the stack of the current thread (or task).
+    fn push(&mut self) {
+        with_stack(|stack| stack.push((self.id, self.name.clone())));
+        self.started = Instant::now();
+        self.entered = true;
+    }
+
+    /// Remove the span from the stack, and get the exit message.
+    fn pop(&mut self) -> String {
+        with_stack(|stack| stack.retain(|(id, _)| *id != self.id));
+        self.entered = false;
+        format!(
+            "exit {} (took {:?})",
+            self.full_name(),
+            self.started.elapsed()
+        )
+    }
+}
+
+#[cfg(feature = "async")]
+impl Span {
+    /// Log the enter of the span, and enter it.
+    pub async fn enter(mut self) -> Self {
+        let message = format!("enter {}", self.full_name());
+        crate::LOGGER
+            .lock()
+            .await
+            .record(self.level, &message, self.position)
+            .await;
+        self.push();
+        self
+    }
+
+    /// Exit the span, and log the exit in the current task.
+    /// (dropping the span queues the exit log, which is written by a spawned task or before the next log)
+    pub async fn exit(mut self) {
+        let message = self.pop();
+        crate::LOGGER
+            .lock()
+            .await
+            .record(self.level, &message, self.position)
+            .await;
+    }
+}
+
+#[cfg(not(feature = "async"))]
+impl Span {
+    /// Log the enter of the span, and enter it.
+    pub fn enter(mut self) -> Self {
+        let message = format!("enter {}", self.full_name());
+        record_detached(self.level, message, self.position);
+        self.push();
+        self
+    }
+
+    /// Exit the span, and log the exit.
+    pub fn exit(self) {}
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        if self.entered {
+            let message = self.pop();
+            record_detached(self.level, message, self.position);
+        }
+    }
+}
@@ -1,4 +1,185 @@
-use chrono::{DateTime, FixedOffset, Utc};
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, FixedOffset, Local, Offset, SecondsFormat, Utc};
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+thread_local! {
+    /// The instant of the previous log on this thread.
+    static PREVIOUS: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+/// The time zone of the log file names and the log message times.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TimeZone {
+    /// a fixed offset from UTC, in minutes. (`330` for +05:30)
+    Offset(i32),
+    /// the local time zone of the system.
+    Local,
+    /// a named IANA time zone, such as `Asia/Kathmandu`, which follows the daylight saving time.
+    #[cfg(feature = "chrono-tz")]
+    Named(chrono_tz::Tz),
+}
+
+impl TimeZone {
+    /// A fixed offset of whole hours from UTC.
+    pub fn hours(hours: i32) -> Self {
+        TimeZone::Offset(hours.saturating_mul(60))
+    }
+
+    /// A fixed offset of minutes from UTC.
+    pub fn minutes(minutes: i32) -> Self {
+        TimeZone::Offset(minutes)
+    }
+
+    /// A named IANA time zone, such as `Asia/Kathmandu`.
+    #[cfg(feature = "chrono-tz")]
+    pub fn named(name: &str) -> Result<Self, TimeZoneError> {
+        name.parse()
+            .map(TimeZone::Named)
+            .map_err(|_| TimeZoneError(format!("unknown time zone {:?}", name)))
+    }
+
+    /// Check whether the time zone can be used.
+    /// A fixed offset must be less than 24 hours.
+    pub fn validate(&self) -> Result<(), TimeZoneError> {
+        match self {
+            TimeZone::Offset(minutes) => minutes
+                .checked_mul(60)
+                .and_then(FixedOffset::east_opt)
+                .map(|_| ())
+                .ok_or_else(|| {
+                    TimeZoneError(format!("offset of {} minutes is out of range", minutes))
+                }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Get the offset from UTC at the given time.
+    /// An invalid fixed offset falls back to UTC.
+    pub(crate) fn offset_at(&self, utc: &DateTime<Utc>) -> FixedOffset {
+        match self {
+            TimeZone::Offset(minutes) => minutes
+                .checked_mul(60)
+                .and_then(FixedOffset::east_opt)
+                .unwrap_or(Utc.fix()),
+            TimeZone::Local => utc.with_timezone(&Local).offset().fix(),
+            #[cfg(feature = "chrono-tz")]
+            TimeZone::Named(tz) => utc.with_timezone(tz).offset().fix(),
+        }
+    }
+
+    /// Format the current time with the time zone.
+    pub(crate) fn format_now(&self, format: &str) -> String {
+        let utc = Utc::now();
+        utc.with_timezone(&self.offset_at(&utc))
+            .format(format)
+            .to_string()
+    }
+}
+
+impl Default for TimeZone {
+    /// UTC as the default time zone.
+    fn default() -> Self {
+        TimeZone::Offset(0)
+    }
+}
+
+impl From<i32> for TimeZone {
+    /// Convert an offset of whole hours, as the time zone was given before.
+    fn from(hours: i32) -> Self {
+        TimeZone::hours(hours)
+    }
+}
+
+/// The precision of the fractional seconds in a [`TimeFormat`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Precision {
+    /// 3 digits.
+    #[default]
+    Millis,
+    /// 6 digits.
+    Micros,
+    /// 9 digits.
+    Nanos,
+}
+
+impl Precision {
+    fn strftime(&self) -> &'static str {
+        match self {
+            Precision::Millis => "%.3f",
+            Precision::Micros => "%.6f",
+            Precision::Nanos => "%.9f",
+        }
+    }
+
+    fn seconds_format(&self) -> SecondsFormat {
+        match self {
+            Precision::Millis => SecondsFormat::Millis,
+            Precision::Micros => SecondsFormat::Micros,
+            Precision::Nanos => SecondsFormat::Nanos,
+        }
+    }
+}
+
+/// The format of the time in each log message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TimeFormat {
+    /// `2025-01-01 08:00:00.000` in the time zone,
+    /// or `2025-01-01 00:00:00.000 (+08:00)` in UTC when the time is detailed displayed.
+    Standard(Precision),
+    /// RFC 3339 with the offset of the time zone. (`2025-01-01T08:00:00.000+08:00`)
+    Rfc3339(Precision),
+    /// seconds since the Unix epoch. (`1735689600`)
+    UnixSeconds,
+    /// milliseconds since the Unix epoch. (`1735689600000`)
+    UnixMillis,
+    /// a custom strftime format in the time zone, such as `%d/%m/%Y %H:%M:%S%.6f %z`.
+    Custom(String),
+}
+
+impl Default for TimeFormat {
+    fn default() -> Self {
+        TimeFormat::Standard(Precision::Millis)
+    }
+}
+
+impl TimeFormat {
+    /// Check whether the format can be used, so that a bad custom format can't panic when writing.
+    pub fn validate(&self) -> Result<(), TimeFormatError> {
+        match self {
+            TimeFormat::Custom(format)
+                if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) =>
+            {
+                Err(TimeFormatError(format.clone()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The error returned when a [`TimeFormat`] is invalid.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TimeFormatError(String);
+
+impl std::fmt::Display for TimeFormatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid time format: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for TimeFormatError {}
+
+/// The error returned when a [`TimeZone`] is invalid.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TimeZoneError(String);
+
+impl std::fmt::Display for TimeZoneError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid time zone: {}", self.0)
+    }
+}
+
+impl std::error::Error for TimeZoneError {}
 
 #[derive(Clone, Debug)]
 pub(crate) struct Time {
@@ -22,26 +203,41 @@ impl Time {
     }
 
     /// Get the current time with specified time zone
-    pub fn now(time_zone: i32) -> Self {
-        Self::new(Utc::now(), FixedOffset::east_opt(time_zone * 3600).unwrap())
+    pub fn now(time_zone: &TimeZone) -> Self {
+        let utc = Utc::now();
+        Self::new(utc, time_zone.offset_at(&utc))
+    }
+
+    /// Get the time passed since an earlier time.
+    pub fn since(&self, earlier: &Time) -> chrono::TimeDelta {
+        self.utc - earlier.utc
     }
 
     /// Format the time
-    fn to_string(&self) -> String {
-        let format = "%Y-%m-%d %H:%M:%S%.3f".to_string();
-        if self.detailed_display {
-            format!("{} ({})", self.utc.format(&format), self.time_offset)
-        } else {
-            (self.utc.with_timezone(&self.time_offset))
-                .format(&format)
-                .to_string()
+    pub fn format(&self, format: &TimeFormat) -> String {
+        let local = self.utc.with_timezone(&self.time_offset);
+        match format {
+            TimeFormat::Standard(precision) => {
+                let format = format!("%Y-%m-%d %H:%M:%S{}", precision.strftime());
+                if self.detailed_display {
+                    format!("{} ({})", self.utc.format(&format), self.time_offset)
+                } else {
+                    local.format(&format).to_string()
+                }
+            }
+            TimeFormat::Rfc3339(precision) => {
+                local.to_rfc3339_opts(precision.seconds_format(), false)
+            }
+            TimeFormat::UnixSeconds => self.utc.timestamp().to_string(),
+            TimeFormat::UnixMillis => self.utc.timestamp_millis().to_string(),
+            TimeFormat::Custom(format) => local.format(format).to_string(),
         }
     }
 }
 
 impl From<&Time> for String {
     fn from(value: &Time) -> Self {
-        value.to_string()
+        value.format(&TimeFormat::default())
     }
 }
 
@@ -53,3 +249,45 @@ impl std::fmt::Display for Time {
 }
 
 unsafe impl Send for Time {}
+
+/// The monotonic time of a log, which does not jump with the wall clock.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub(crate) struct Elapsed {
+    /// the time since the logger started.
+    since_start: Option<Duration>,
+    /// the time since the previous log on the same thread.
+    delta: Option<Duration>,
+}
+
+impl Elapsed {
+    /// Measure the enabled durations at the call site.
+    pub fn measure(started: Instant, since_start: bool, delta: bool) -> Self {
+        let now = Instant::now();
+        let previous = PREVIOUS.with(|previous| previous.replace(Some(now)));
+        Self {
+            since_start: since_start.then(|| now.duration_since(started)),
+            delta: delta
+                .then(|| previous.map_or(Duration::ZERO, |previous| now.duration_since(previous))),
+        }
+    }
+
+    /// Check whether no duration is measured.
+    pub fn is_empty(&self) -> bool {
+        self.since_start.is_none() && self.delta.is_none()
+    }
+}
+
+impl std::fmt::Display for Elapsed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let fields: Vec<String> = [
+            self.since_start
+                .map(|since_start| format!("{:.6}s", since_start.as_secs_f64())),
+            self.delta
+                .map(|delta| format!("+{:.6}s", delta.as_secs_f64())),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        write!(f, "{}", fields.join(" "))
+    }
+}
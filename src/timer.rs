@@ -0,0 +1,57 @@
+use super::{record_detached, Location, LogLevel};
+use std::time::{Duration, Instant};
+
+/// A guard that logs the time elapsed since it was created when it is dropped.
+/// The position is captured when it is created, usually by [`timer!`](crate::timer).
+#[derive(Debug)]
+pub struct LogTimer {
+    /// the level of the log.
+    level: LogLevel,
+    /// the name of the measured work.
+    name: String,
+    /// where the timer is created.
+    position: Location,
+    /// when the timer is created.
+    started: Instant,
+    /// the threshold and the level to escalate to when the work is slow.
+    slow: Option<(Duration, LogLevel)>,
+}
+
+impl LogTimer {
+    /// Start a timer.
+    pub fn new(level: LogLevel, name: &str, position: Location) -> Self {
+        Self {
+            level,
+            name: name.to_string(),
+            position,
+            started: Instant::now(),
+            slow: None,
+        }
+    }
+
+    /// Escalate the log to the level when the elapsed time exceeds the threshold.
+    pub fn slow(mut self, threshold: Duration, level: LogLevel) -> Self {
+        self.slow = Some((threshold, level));
+        self
+    }
+
+    /// Get the time elapsed since the timer started.
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+}
+
+impl Drop for LogTimer {
+    fn drop(&mut self) {
+        let elapsed = self.elapsed();
+        let level = match self.slow {
+            Some((threshold, level)) if elapsed > threshold => level,
+            _ => self.level,
+        };
+        record_detached(
+            level,
+            format!("{} took {:?}", self.name, elapsed),
+            self.position,
+        );
+    }
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use common::read_logs;
+use layla_log::*;
+use std::time::Duration;
+
+fn setting() -> Setting {
+    Setting {
+        buffer_size: 1 << 20,
+        buffer_latency: Duration::from_millis(200),
+        ..Default::default()
+    }
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn buffer() {
+    clean_log();
+    init(setting());
+
+    info!("This is an info message");
+    assert_eq!(read_logs().lines().count(), 0);
+    error!("This is an error message");
+    assert_eq!(read_logs().lines().count(), 2);
+    warn!("This is a warning message");
+    assert_eq!(read_logs().lines().count(), 2);
+    std::thread::sleep(Duration::from_secs(1));
+    assert_eq!(read_logs().lines().count(), 3);
+    debug!("This is a debug message");
+    flush();
+    assert_eq!(read_logs().lines().count(), 4);
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn buffer() {
+    clean_log().await;
+    init(setting()).await;
+
+    info!("This is an info message");
+    assert_eq!(read_logs().lines().count(), 0);
+    error!("This is an error message");
+    assert_eq!(read_logs().lines().count(), 2);
+    warn!("This is a warning message");
+    assert_eq!(read_logs().lines().count(), 2);
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    assert_eq!(read_logs().lines().count(), 3);
+    debug!("This is a debug message");
+    flush().await;
+    assert_eq!(read_logs().lines().count(), 4);
+}
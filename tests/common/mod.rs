@@ -0,0 +1,15 @@
+//! The helpers shared by the integration tests.
+
+/// Read the log files in `./logs` in the order of their names, concatenated.
+pub fn read_logs() -> String {
+    let mut files: Vec<_> = std::fs::read_dir("./logs")
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    files.sort();
+    let mut logs = String::new();
+    for file in files {
+        logs += &std::fs::read_to_string(file).unwrap();
+    }
+    logs
+}
@@ -0,0 +1,83 @@
+mod common;
+
+use common::read_logs;
+use layla_log::*;
+
+fn setting() -> Setting {
+    Setting {
+        record_thread_name: true,
+        record_thread_id: true,
+        record_process_id: true,
+        record_hostname: true,
+        record_task_id: true,
+        ..Default::default()
+    }
+}
+
+fn find_line<'a>(logs: &'a str, message: &str) -> &'a str {
+    let lines: Vec<&str> = logs.lines().filter(|line| line.contains(message)).collect();
+    assert_eq!(lines.len(), 1, "{logs}");
+    assert!(lines[0].contains("pid="), "{logs}");
+    assert!(lines[0].contains("host="), "{logs}");
+    lines[0]
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn context() {
+    clean_log();
+    init(setting());
+
+    let handles: Vec<_> = (0..4)
+        .map(|i| {
+            std::thread::Builder::new()
+                .name(format!("worker-{}", i))
+                .spawn(move || info!("hello from worker {}", i))
+                .unwrap()
+        })
+        .collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+
+    let logs = read_logs();
+    for i in 0..4 {
+        let line = find_line(&logs, &format!("hello from worker {}", i));
+        assert!(line.contains(&format!("thread=worker-{} ", i)), "{logs}");
+    }
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn context() {
+    clean_log().await;
+    init(setting()).await;
+
+    let handles: Vec<_> = (0..4)
+        .map(|i| {
+            tokio::spawn(async move {
+                info!("hello from task {}", i);
+                // a detached log, written later by another task.
+                drop(timer!(LogLevel::Info, &format!("timer of task {}", i)));
+                tokio::task::id()
+            })
+        })
+        .collect();
+    let mut ids = Vec::new();
+    for handle in handles {
+        ids.push(handle.await.unwrap());
+    }
+    // the queued logs of the timers are written before the next log.
+    info!("done");
+
+    let logs = read_logs();
+    for (i, id) in ids.into_iter().enumerate() {
+        for message in [
+            format!("hello from task {}", i),
+            format!("timer of task {}", i),
+        ] {
+            let line = find_line(&logs, &message);
+            assert!(line.contains(&format!("task={})", id)), "{logs}");
+        }
+    }
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use common::read_logs;
+use layla_log::*;
+
+const AUDIT: LogLevel = LogLevel::Custom(CustomLevel::new("AUDIT", 250).always_record());
+
+#[test]
+fn custom_level_order_and_parse() {
+    assert!(LogLevel::Info < AUDIT);
+    assert!(AUDIT < LogLevel::Warn);
+
+    assert!("audit".parse::<LogLevel>().is_err());
+    assert_eq!(
+        LogLevel::register(CustomLevel::new("AUDIT", 250).always_record()),
+        AUDIT
+    );
+    assert_eq!(AUDIT.to_string().parse::<LogLevel>(), Ok(AUDIT));
+}
+
+/// The audit log is written even though the file records nothing, and the info log is not.
+fn check(logs: &str) {
+    let lines: Vec<&str> = logs.lines().collect();
+    assert_eq!(lines.len(), 1, "{}", logs);
+    assert!(lines[0].contains("AUDIT") && lines[0].ends_with("This is an audit message"));
+    assert!(!logs.contains("This should not be recorded"));
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn custom_level_always_recorded() {
+    clean_log();
+    init(Setting {
+        file_record_level: LogLevel::Off,
+        ..Default::default()
+    });
+
+    log!(AUDIT, "This is an audit message");
+    info!("This should not be recorded");
+
+    check(&read_logs());
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn custom_level_always_recorded() {
+    clean_log().await;
+    init(Setting {
+        file_record_level: LogLevel::Off,
+        ..Default::default()
+    })
+    .await;
+
+    log!(AUDIT, "This is an audit message");
+    info!("This should not be recorded");
+
+    check(&read_logs());
+}
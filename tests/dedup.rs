@@ -0,0 +1,72 @@
+mod common;
+
+use common::read_logs;
+use layla_log::*;
+use std::time::Duration;
+
+fn setting() -> Setting {
+    Setting {
+        dedup_window: Some(Duration::from_millis(500)),
+        ..Default::default()
+    }
+}
+
+fn check_run() {
+    let logs = read_logs();
+    let lines: Vec<&str> = logs.lines().collect();
+    assert_eq!(lines.len(), 3, "{logs}");
+    assert!(lines[0].contains("connection lost"), "{logs}");
+    assert!(
+        lines[1].contains("last message repeated 99 times"),
+        "{logs}"
+    );
+    assert!(lines[2].contains("connection restored"), "{logs}");
+}
+
+fn check_expired() {
+    let logs = read_logs();
+    let lines: Vec<&str> = logs.lines().collect();
+    assert_eq!(lines.len(), 5, "{logs}");
+    assert!(lines[3].contains("link flapping"), "{logs}");
+    assert!(lines[4].contains("last message repeated 2 times"), "{logs}");
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn dedup() {
+    clean_log();
+    init(setting());
+
+    for _ in 0..100 {
+        warn!("connection lost");
+    }
+    info!("connection restored");
+    check_run();
+
+    for _ in 0..3 {
+        warn!("link flapping");
+    }
+    // the run is summarized by the timer once the window expires, without another log.
+    std::thread::sleep(Duration::from_millis(1500));
+    check_expired();
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn dedup() {
+    clean_log().await;
+    init(setting()).await;
+
+    for _ in 0..100 {
+        warn!("connection lost");
+    }
+    info!("connection restored");
+    check_run();
+
+    for _ in 0..3 {
+        warn!("link flapping");
+    }
+    // the run is summarized by the timer once the window expires, without another log.
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    check_expired();
+}
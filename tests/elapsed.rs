@@ -0,0 +1,52 @@
+mod common;
+
+use common::read_logs;
+use layla_log::*;
+use std::time::Duration;
+
+fn setting() -> Setting {
+    Setting {
+        record_elapsed: true,
+        record_delta: true,
+        ..Default::default()
+    }
+}
+
+/// Parse the delta of the `[..s +..s]` column.
+fn delta(line: &str) -> Duration {
+    let start = line.find(" +").unwrap() + 2;
+    let end = start + line[start..].find("s]").unwrap();
+    Duration::from_secs_f64(line[start..end].parse().unwrap())
+}
+
+fn check() {
+    let logs = read_logs();
+    let lines: Vec<&str> = logs.lines().collect();
+    assert_eq!(lines.len(), 2, "{logs}");
+    assert!(lines[1].contains("request handled"), "{logs}");
+    assert!(delta(lines[1]) >= Duration::from_micros(300), "{logs}");
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn elapsed() {
+    clean_log();
+    init(setting());
+
+    info!("request received");
+    std::thread::sleep(Duration::from_micros(300));
+    info!("request handled");
+    check();
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn elapsed() {
+    clean_log().await;
+    init(setting()).await;
+
+    info!("request received");
+    tokio::time::sleep(Duration::from_micros(300)).await;
+    info!("request handled");
+    check();
+}
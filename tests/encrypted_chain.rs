@@ -0,0 +1,94 @@
+#![cfg(all(feature = "encryption", feature = "integrity"))]
+
+use layla_log::*;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Set in the child processes, which write the logs as separate runs of the program.
+const CHILD: &str = "LAYLA_LOG_ENCRYPTED_CHAIN_CHILD";
+
+const SIGNING_KEY: &[u8] = b"0123456789abcdef";
+
+fn key() -> SecretKey {
+    SecretKey::new(*b"an example key of thirty-two b!!")
+}
+
+fn setting() -> Setting {
+    Setting {
+        hash_chain: true,
+        signing_key: Some(SecretKey::new(SIGNING_KEY)),
+        encryption_key: Some(key()),
+        ..Default::default()
+    }
+}
+
+/// Run the test again in a new process, which writes two logs.
+fn run_child() {
+    let status = Command::new(std::env::current_exe().unwrap())
+        .args(["--exact", "encrypted_chain", "--nocapture"])
+        .env(CHILD, "1")
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
+
+/// Decrypt the files written by both runs, and verify them as one chain and one sequence.
+fn check() {
+    let dir = std::env::temp_dir().join("layla-log-encrypted-chain");
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    // the encrypted files cannot be verified before they are decrypted
+    assert_eq!(verify("./logs").unwrap_err().line, 0);
+    let error = verify_signatures("./logs", SIGNING_KEY).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+    let mut files: Vec<PathBuf> = std::fs::read_dir("./logs")
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    files.sort();
+    for file in files {
+        let name = file.file_name().unwrap().to_str().unwrap();
+        let name = name.strip_suffix(".enc").unwrap();
+        std::fs::write(dir.join(name), decrypt_file(&file, &key()).unwrap()).unwrap();
+    }
+
+    let report = verify_signatures(&dir, SIGNING_KEY).unwrap();
+    assert!(report.is_valid(), "{:?}", report.issues);
+    assert_eq!(report.verified, 4);
+    assert_eq!(verify(&dir), Ok(8));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn encrypted_chain() {
+    if std::env::var_os(CHILD).is_some() {
+        init(setting());
+        info!("first log of the run");
+        info!("second log of the run");
+        return;
+    }
+
+    clean_log();
+    run_child();
+    run_child();
+    check();
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn encrypted_chain() {
+    if std::env::var_os(CHILD).is_some() {
+        init(setting()).await;
+        info!("first log of the run");
+        info!("second log of the run");
+        return;
+    }
+
+    clean_log().await;
+    run_child();
+    run_child();
+    check();
+}
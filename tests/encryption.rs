@@ -0,0 +1,110 @@
+#![cfg(feature = "encryption")]
+
+use layla_log::*;
+use std::io::{ErrorKind, Read};
+use std::path::PathBuf;
+
+fn key() -> SecretKey {
+    SecretKey::new(*b"an example key of thirty-two b!!")
+}
+
+fn setting() -> Setting {
+    Setting {
+        single_length: 2,
+        encryption_key: Some(key()),
+        ..Default::default()
+    }
+}
+
+fn log_files() -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir("./logs")
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    files.sort();
+    files
+}
+
+fn check() {
+    let files = log_files();
+    assert_eq!(files.len(), 2);
+    assert!(files
+        .iter()
+        .all(|file| file.to_str().unwrap().ends_with(".log.enc")));
+
+    let bytes = std::fs::read(&files[0]).unwrap();
+    assert!(!String::from_utf8_lossy(&bytes).contains("card holder"));
+    let logs = decrypt_file(&files[0], &key()).unwrap();
+    assert_eq!(logs.lines().count(), 2);
+    assert!(logs.lines().next().unwrap().ends_with("card holder 0"));
+    assert!(decrypt_file(&files[1], &key())
+        .unwrap()
+        .ends_with("card holder 2\n"));
+
+    // a crash only loses the chunk being written
+    let mut logs = String::new();
+    DecryptReader::new(&bytes[..bytes.len() - 5], &key())
+        .unwrap()
+        .read_to_string(&mut logs)
+        .unwrap();
+    assert_eq!(logs.lines().count(), 1);
+
+    // an altered chunk is an error
+    let mut altered = bytes.clone();
+    let last = altered.len() - 1;
+    altered[last] ^= 1;
+    let error = DecryptReader::new(&altered[..], &key())
+        .unwrap()
+        .read_to_string(&mut String::new())
+        .unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+
+    let wrong = SecretKey::new([0; 32]);
+    assert!(decrypt_file(&files[0], &wrong).is_err());
+}
+
+/// The file written after cleaning the logs has the same path as before, and still begins with the header.
+fn check_after_clean() {
+    let files = log_files();
+    assert_eq!(files.len(), 1);
+    assert!(decrypt_file(&files[0], &key())
+        .unwrap()
+        .ends_with("after clean\n"));
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn encryption() {
+    clean_log();
+    init(setting());
+
+    for i in 0..3 {
+        info!("card holder {}", i);
+    }
+
+    check();
+
+    clean_log();
+    info!("before clean");
+    clean_log();
+    info!("after clean");
+    check_after_clean();
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn encryption() {
+    clean_log().await;
+    init(setting()).await;
+
+    for i in 0..3 {
+        info!("card holder {}", i);
+    }
+    check();
+
+    clean_log().await;
+    info!("before clean");
+    clean_log().await;
+    info!("after clean");
+    check_after_clean();
+}
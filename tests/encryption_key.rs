@@ -0,0 +1,61 @@
+#![cfg(feature = "encryption")]
+
+use layla_log::*;
+use std::process::Command;
+
+/// Set in the child process, which is initialized with the invalid key.
+const CHILD: &str = "LAYLA_LOG_ENCRYPTION_KEY_CHILD";
+
+fn setting() -> Setting {
+    Setting {
+        encryption_key: Some(SecretKey::new(*b"too short")),
+        ..Default::default()
+    }
+}
+
+/// Run the test again in a new process, and get what it printed to the standard error.
+fn run_child() -> String {
+    let output = Command::new(std::env::current_exe().unwrap())
+        .args(["--exact", "invalid_key", "--nocapture"])
+        .env(CHILD, "1")
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stderr).unwrap()
+}
+
+/// The invalid key is reported, and no file is created for the logs that cannot be encrypted.
+fn check(stderr: &str) {
+    assert!(stderr.contains(
+        "The encryption key has 9 bytes, but 32 bytes are expected, the logs are not written into the files."
+    ));
+    assert_eq!(std::fs::read_dir("./logs").unwrap().count(), 0);
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn invalid_key() {
+    if std::env::var_os(CHILD).is_some() {
+        init(setting());
+        info!("the card holder");
+        return;
+    }
+
+    clean_log();
+    let stderr = run_child();
+    check(&stderr);
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn invalid_key() {
+    if std::env::var_os(CHILD).is_some() {
+        init(setting()).await;
+        info!("the card holder");
+        return;
+    }
+
+    clean_log().await;
+    let stderr = run_child();
+    check(&stderr);
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use common::read_logs;
+use layla_log::*;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug)]
+struct ConfigError(std::io::Error);
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cannot read config.toml")
+    }
+}
+
+impl Error for ConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+fn load_config() -> Result<(), ConfigError> {
+    Err(ConfigError(std::io::Error::other("permission denied")))
+}
+
+/// Each log is followed by the line of the source of its error, including the boxed one.
+/// (a backtrace may follow, when it is enabled by `RUST_BACKTRACE`)
+fn check() {
+    let logs = read_logs();
+    let lines: Vec<&str> = logs.lines().collect();
+    for (level, message) in [
+        ("ERROR", "failed to load config: cannot read config.toml"),
+        (
+            "WARN",
+            "failed to load config, the default will be used: cannot read config.toml",
+        ),
+    ] {
+        let index = lines
+            .iter()
+            .position(|line| line.ends_with(message))
+            .unwrap_or_else(|| panic!("{message:?} in {logs}"));
+        assert!(lines[index].contains(level), "{logs}");
+        assert!(
+            lines[index + 1].ends_with("caused by: permission denied"),
+            "{logs}"
+        );
+    }
+}
+
+#[test]
+fn chain() {
+    let error = load_config().unwrap_err();
+    assert!(error_chain("failed to load config", &error).starts_with(
+        "failed to load config: cannot read config.toml\ncaused by: permission denied"
+    ));
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn log_error_chain() {
+    clean_log();
+
+    if let Err(e) = load_config() {
+        error!(err: e, "failed to load config");
+    }
+    let boxed: Box<dyn Error> = Box::new(load_config().unwrap_err());
+    warn!(err: boxed.as_ref(), "failed to load config, {} will be used", "the default");
+
+    check();
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn log_error_chain() {
+    clean_log().await;
+
+    if let Err(e) = load_config() {
+        error!(err: e, "failed to load config");
+    }
+    let boxed: Box<dyn Error + Send + Sync> = Box::new(load_config().unwrap_err());
+    warn!(err: boxed.as_ref(), "failed to load config, {} will be used", "the default");
+
+    check();
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use common::read_logs;
+use layla_log::*;
+use std::collections::HashMap;
+
+fn parse_port(port: &str) -> Result<u16, std::num::ParseIntError> {
+    port.parse()
+}
+
+/// The logs of the error path are written in order, before the log after them.
+fn check(logs: &str) {
+    let expected = [
+        "invalid port: invalid digit found in string",
+        "invalid digit found in string, the default value is used",
+        "missing key",
+        "no value, the default value is used",
+        "no port",
+        "port 80",
+        "after the error path",
+    ];
+    let lines: Vec<&str> = logs.lines().collect();
+    assert_eq!(lines.len(), expected.len(), "{}", logs);
+    for (line, expected) in lines.iter().zip(expected) {
+        assert!(line.contains(expected), "{:?} in {:?}", expected, line);
+        assert!(line.contains("tests/ext.rs:"), "{}", line);
+    }
+}
+
+#[test]
+fn caller_location() {
+    let location = Location::caller();
+    assert_eq!(location.file, file!());
+    assert_eq!(location.line, line!() - 2);
+    assert_eq!(
+        location.to_string(),
+        format!("{}:{}:{}", file!(), location.line, location.column)
+    );
+}
+
+#[test]
+fn pass_through() {
+    assert_eq!(
+        parse_port("8080").log_err(LogLevel::Error, "invalid port"),
+        Ok(8080)
+    );
+    assert_eq!(parse_port("8080").unwrap_or_log(80), 8080);
+    assert_eq!(Some(1).log_warn_none("missing key"), Some(1));
+    assert_eq!(Some(1).unwrap_or_log(0), 1);
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn log_on_error_path() {
+    clean_log();
+
+    let config: HashMap<&str, &str> = HashMap::new();
+    assert!(parse_port("http")
+        .log_err(LogLevel::Error, "invalid port")
+        .is_err());
+    assert_eq!(parse_port("http").unwrap_or_log(80), 80);
+    assert_eq!(config.get("port").log_warn_none("missing key"), None);
+    assert_eq!(
+        config.get("host").copied().unwrap_or_log("localhost"),
+        "localhost"
+    );
+    // the argument logs on its error path, while the outer log is being recorded.
+    let port: Option<u16> = None;
+    info!("port {}", port.log_warn_none("no port").unwrap_or(80));
+    info!("after the error path");
+
+    check(&read_logs());
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn log_on_error_path() {
+    clean_log().await;
+
+    let config: HashMap<&str, &str> = HashMap::new();
+    assert!(parse_port("http")
+        .log_err(LogLevel::Error, "invalid port")
+        .is_err());
+    assert_eq!(parse_port("http").unwrap_or_log(80), 80);
+    assert_eq!(config.get("port").log_warn_none("missing key"), None);
+    assert_eq!(
+        config.get("host").copied().unwrap_or_log("localhost"),
+        "localhost"
+    );
+    // the argument logs on its error path, while the outer log is being recorded.
+    let port: Option<u16> = None;
+    info!("port {}", port.log_warn_none("no port").unwrap_or(80));
+    info!("after the error path");
+
+    check(&read_logs());
+}
@@ -0,0 +1,86 @@
+use layla_log::*;
+
+struct Session {
+    id: u64,
+    user: String,
+}
+
+impl LogFields for Session {
+    fn log_fields(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("session", self.id.to_string()),
+            ("user", self.user.clone()),
+        ]
+    }
+}
+
+#[cfg(feature = "macros")]
+#[derive(LogFields)]
+#[allow(dead_code)]
+struct Order {
+    #[log(rename = "order_id")]
+    id: u32,
+    customer: String,
+    #[log(redact)]
+    card: String,
+    #[log(skip)]
+    notes: Vec<String>,
+}
+
+#[cfg(feature = "macros")]
+fn order() -> Order {
+    Order {
+        id: 7,
+        customer: "layla".to_string(),
+        card: "4111 1111 1111 1111".to_string(),
+        notes: vec!["fragile".to_string()],
+    }
+}
+
+#[test]
+fn format_fields() {
+    let session = Session {
+        id: 42,
+        user: "layla".to_string(),
+    };
+    assert_eq!(session.format_fields(), "session=42 user=layla");
+}
+
+#[cfg(feature = "macros")]
+#[test]
+fn derive_fields() {
+    assert_eq!(
+        order().format_fields(),
+        "order_id=7 customer=\"layla\" card=***"
+    );
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn log_fields() {
+    clean_log();
+
+    let session = Session {
+        id: 42,
+        user: "layla".to_string(),
+    };
+    info!(session; "logged in");
+    warn!(&session; "session {} expires soon", session.id);
+    #[cfg(feature = "macros")]
+    info!(order(); "created");
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn log_fields() {
+    clean_log().await;
+
+    let session = Session {
+        id: 42,
+        user: "layla".to_string(),
+    };
+    info!(session; "logged in");
+    warn!(&session; "session {} expires soon", session.id);
+    #[cfg(feature = "macros")]
+    info!(order(); "created");
+}
@@ -0,0 +1,71 @@
+#![cfg(feature = "integrity")]
+
+use layla_log::*;
+use std::path::PathBuf;
+
+fn setting() -> Setting {
+    Setting {
+        single_length: 3,
+        hash_chain: true,
+        ..Default::default()
+    }
+}
+
+fn log_file(index: usize) -> PathBuf {
+    let mut files: Vec<PathBuf> = std::fs::read_dir("./logs")
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    files.sort();
+    files.remove(index)
+}
+
+fn check() {
+    assert_eq!(verify("./logs"), Ok(7));
+
+    // no log file in the directory
+    std::fs::create_dir_all("./logs/empty").unwrap();
+    let broken = verify("./logs/empty").unwrap_err();
+    assert_eq!(
+        (broken.file, broken.line),
+        (PathBuf::from("./logs/empty"), 0)
+    );
+    std::fs::remove_dir("./logs/empty").unwrap();
+
+    // edit a log in the second file
+    let file = log_file(1);
+    let content = std::fs::read_to_string(&file).unwrap();
+    std::fs::write(&file, content.replace("log 4", "log 5")).unwrap();
+    let broken = verify("./logs").unwrap_err();
+    assert_eq!((broken.file, broken.line), (file.clone(), 3));
+    std::fs::write(&file, content).unwrap();
+
+    // remove the second file
+    std::fs::remove_file(&file).unwrap();
+    let broken = verify("./logs").unwrap_err();
+    assert_eq!((broken.file, broken.line), (log_file(1), 1));
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn hash_chain() {
+    clean_log();
+    init(setting());
+
+    for i in 0..7 {
+        info!("log {}", i);
+    }
+    check();
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn hash_chain() {
+    clean_log().await;
+    init(setting()).await;
+
+    for i in 0..7 {
+        info!("log {}", i);
+    }
+    check();
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use common::read_logs;
+use layla_log::*;
+
+#[test]
+fn level_order_and_parse() {
+    assert!(LogLevel::Trace < LogLevel::Debug);
+    assert!(LogLevel::Error < LogLevel::Fatal);
+    assert!(LogLevel::Fatal < LogLevel::Off);
+
+    for level in [
+        LogLevel::Trace,
+        LogLevel::Debug,
+        LogLevel::Info,
+        LogLevel::Warn,
+        LogLevel::Error,
+        LogLevel::Fatal,
+        LogLevel::Off,
+    ] {
+        assert_eq!(level.to_string().parse::<LogLevel>(), Ok(level));
+    }
+    assert_eq!("warn".parse::<LogLevel>(), Ok(LogLevel::Warn));
+    assert!("verbose".parse::<LogLevel>().is_err());
+}
+
+fn check() {
+    let logs = read_logs();
+    let lines: Vec<&str> = logs.lines().collect();
+    assert_eq!(lines.len(), 1, "{logs}");
+    assert!(lines[0].contains("FATAL"), "{logs}");
+    assert!(lines[0].contains("This is a fatal message"), "{logs}");
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn fatal_and_off() {
+    clean_log();
+    init(Setting {
+        terminal_print_level: LogLevel::Off,
+        ..Default::default()
+    });
+
+    fatal!("This is a fatal message");
+    log!(LogLevel::Off, "This should not be logged");
+    check();
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn fatal_and_off() {
+    clean_log().await;
+    init(Setting {
+        terminal_print_level: LogLevel::Off,
+        ..Default::default()
+    })
+    .await;
+
+    fatal!("This is a fatal message");
+    log!(LogLevel::Off, "This should not be logged");
+    check();
+}
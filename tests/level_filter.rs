@@ -0,0 +1,62 @@
+mod common;
+
+use common::read_logs;
+use layla_log::*;
+
+const NOTICE: LogLevel = LogLevel::Custom(CustomLevel::new("NOTICE", 200));
+const AUDIT: LogLevel = LogLevel::Custom(CustomLevel::new("AUDIT", 200));
+
+fn setting() -> Setting {
+    Setting {
+        file_record_level: LogLevel::Info,
+        ..Default::default()
+    }
+}
+
+/// The levels with the same severity as the threshold are all written, and the level column is aligned.
+fn check(logs: &str) {
+    let lines: Vec<&str> = logs.lines().collect();
+    assert_eq!(lines.len(), 3, "{}", logs);
+    assert!(lines[0].contains("NOTICE [") && lines[0].ends_with("notice"));
+    assert!(lines[1].contains("AUDIT  [") && lines[1].ends_with("audit"));
+    assert!(lines[2].contains("INFO   [") && lines[2].ends_with("info"));
+    let column = lines[0].find(" [").unwrap();
+    assert!(lines.iter().all(|line| line.find(" [") == Some(column)));
+}
+
+#[test]
+fn severity_only() {
+    assert!(AUDIT.is_at_least(LogLevel::Info));
+    assert!(NOTICE.is_at_least(AUDIT) && AUDIT.is_at_least(NOTICE));
+    assert!(!LogLevel::Debug.is_at_least(AUDIT));
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn level_filter() {
+    LogLevel::register(CustomLevel::new("NOTICE", 200));
+    clean_log();
+    init(setting());
+
+    log!(NOTICE, "notice");
+    log!(AUDIT, "audit");
+    info!("info");
+    debug!("debug");
+
+    check(&read_logs());
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn level_filter() {
+    LogLevel::register(CustomLevel::new("NOTICE", 200));
+    clean_log().await;
+    init(setting()).await;
+
+    log!(NOTICE, "notice");
+    log!(AUDIT, "audit");
+    info!("info");
+    debug!("debug");
+
+    check(&read_logs());
+}
@@ -0,0 +1,139 @@
+#![cfg(feature = "macros")]
+
+mod common;
+
+use common::read_logs;
+use layla_log::*;
+
+#[logged(level = "info", skip(password))]
+fn login(user: &str, password: &str) -> bool {
+    !user.is_empty() && !password.is_empty()
+}
+
+#[logged]
+fn parse_port(port: &str) -> Result<u16, std::num::ParseIntError> {
+    let port = port.trim().parse()?;
+    Ok(port)
+}
+
+struct Cache(Vec<u32>);
+
+impl Cache {
+    #[logged(level = "trace")]
+    fn get(&self, index: usize) -> Option<u32> {
+        if index >= self.0.len() {
+            return None;
+        }
+        self.0.get(index).copied()
+    }
+}
+
+struct Counter {
+    values: Vec<u32>,
+}
+
+impl Counter {
+    #[logged]
+    fn first(&mut self) -> Option<&mut u32> {
+        self.values.first_mut()
+    }
+
+    #[logged]
+    fn second(&self) -> Option<u32> {
+        let second = self.values.get(1)?;
+        Some(*second)
+    }
+}
+
+/// The entry and exit logs of the synchronous functions, in order.
+const EXPECTED: [(&str, &str); 17] = [
+    ("INFO", "enter login(user = \"layla\")"),
+    ("INFO", "exit login -> true (took "),
+    ("DEBUG", "enter parse_port(port = \" 8080\")"),
+    ("DEBUG", "exit parse_port -> Ok(8080) (took "),
+    ("DEBUG", "enter parse_port(port = \"http\")"),
+    (
+        "ERROR",
+        "exit parse_port -> Err(ParseIntError { kind: InvalidDigit }) (took ",
+    ),
+    ("TRACE", "enter get(index = 1)"),
+    ("TRACE", "exit get -> Some(2) (took "),
+    ("TRACE", "enter get(index = 5)"),
+    ("TRACE", "exit get -> None (took "),
+    ("DEBUG", "enter first()"),
+    ("DEBUG", "exit first -> Some(1) (took "),
+    ("DEBUG", "enter second()"),
+    ("DEBUG", "exit second -> None (took "),
+    ("DEBUG", "enter parse_port(port = \"443\")"),
+    ("DEBUG", "exit parse_port -> Ok(443) (took "),
+    ("INFO", "port Ok(443)"),
+];
+
+fn check(logs: &str, expected: &[(&str, &str)]) {
+    let lines: Vec<&str> = logs.lines().collect();
+    assert_eq!(lines.len(), expected.len(), "{}", logs);
+    for (line, (level, message)) in lines.iter().zip(expected) {
+        assert!(
+            line.contains(level) && line.contains(message),
+            "{:?} in {:?}",
+            message,
+            line
+        );
+    }
+}
+
+fn call_sync() {
+    assert!(login("layla", "secret"));
+    assert_eq!(parse_port(" 8080"), Ok(8080));
+    assert!(parse_port("http").is_err());
+    let cache = Cache(vec![1, 2]);
+    assert_eq!(cache.get(1), Some(2));
+    assert_eq!(cache.get(5), None);
+    let mut counter = Counter { values: vec![1] };
+    *counter.first().unwrap() += 1;
+    assert_eq!(counter.values, vec![2]);
+    assert_eq!(counter.second(), None);
+}
+
+#[cfg(feature = "async")]
+#[logged(level = "warn")]
+async fn fetch(id: u32) -> Result<String, String> {
+    tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+    if id == 0 {
+        return Err("not found".to_string());
+    }
+    Ok(format!("item {}", id))
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn logged_fn() {
+    clean_log();
+
+    call_sync();
+    // the function logs while the outer log is being recorded.
+    info!("port {:?}", parse_port("443"));
+
+    check(&read_logs(), &EXPECTED);
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn logged_fn() {
+    clean_log().await;
+
+    call_sync();
+    // the function logs while the outer log is being recorded.
+    info!("port {:?}", parse_port("443"));
+    assert_eq!(fetch(1).await, Ok("item 1".to_string()));
+    assert!(fetch(0).await.is_err());
+
+    let mut expected = EXPECTED.to_vec();
+    expected.extend([
+        ("WARN", "enter fetch(id = 1)"),
+        ("WARN", "exit fetch -> Ok(\"item 1\") (took "),
+        ("WARN", "enter fetch(id = 0)"),
+        ("ERROR", "exit fetch -> Err(\"not found\") (took "),
+    ]);
+    check(&read_logs(), &expected);
+}
@@ -1,6 +1,3 @@
-#![feature(test)]
-
-extern crate test;
 use layla_log::*;
 
 #[cfg(not(feature = "async"))]
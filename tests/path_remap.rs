@@ -0,0 +1,39 @@
+mod common;
+
+use common::read_logs;
+use layla_log::*;
+
+fn setting() -> Setting {
+    Setting {
+        path_remap: vec![("tests/".to_string(), "<tests>/".to_string())],
+        // not a whole component of `<tests>/path_remap.rs`, so it is not stripped.
+        path_strip_prefix: Some("<tests>/path".to_string()),
+        normalize_path_separators: true,
+        ..Default::default()
+    }
+}
+
+fn check() {
+    let logs = read_logs();
+    assert!(logs.contains(" @ <tests>/path_remap.rs:"), "{logs}");
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn path_remap() {
+    clean_log();
+    init(setting());
+
+    info!("This is an info message");
+    check();
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn path_remap() {
+    clean_log().await;
+    init(setting()).await;
+
+    info!("This is an info message");
+    check();
+}
@@ -0,0 +1,53 @@
+use layla_log::*;
+
+mod db {
+    pub struct Pool;
+
+    impl Pool {
+        pub fn connect() -> layla_log::Location {
+            let f = || layla_log::position!();
+            f()
+        }
+    }
+}
+
+#[test]
+fn location() {
+    let location = db::Pool::connect();
+    assert_eq!(location.module_path, "position::db");
+    assert_eq!(location.function, "position::db::Pool::connect");
+    assert_eq!(location.short_function(), "connect");
+    assert_eq!(location.file, file!());
+    assert_eq!(
+        location.to_string(),
+        format!(
+            "connect @ {}:{}:{}",
+            location.file, location.line, location.column
+        )
+    );
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn full_position() {
+    clean_log();
+    init(Setting {
+        position_format: PositionFormat::Full,
+        ..Default::default()
+    });
+
+    info!("This is an info message");
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn full_position() {
+    clean_log().await;
+    init(Setting {
+        position_format: PositionFormat::Full,
+        ..Default::default()
+    })
+    .await;
+
+    info!("This is an info message");
+}
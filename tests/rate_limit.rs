@@ -0,0 +1,53 @@
+mod common;
+
+use common::read_logs;
+use layla_log::*;
+use std::collections::HashMap;
+use std::time::Duration;
+
+fn setting() -> Setting {
+    Setting {
+        rate_limits: HashMap::from([(LogLevel::Info, RateLimit::per_second(10))]),
+        global_rate_limit: Some(RateLimit::per_second(1000)),
+        rate_limit_report_interval: Duration::from_millis(200),
+        ..Default::default()
+    }
+}
+
+fn check() {
+    let logs = read_logs();
+    assert_eq!(logs.matches("loop ").count(), 10, "{logs}");
+    assert_eq!(
+        logs.matches("90 logs suppressed by the rate limit").count(),
+        1,
+        "{logs}"
+    );
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn rate_limit() {
+    clean_log();
+    init(setting());
+
+    for i in 0..100 {
+        info!("loop {}", i);
+    }
+    // the dropped logs are reported by the timer, without another log.
+    std::thread::sleep(Duration::from_millis(600));
+    check();
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn rate_limit() {
+    clean_log().await;
+    init(setting()).await;
+
+    for i in 0..100 {
+        info!("loop {}", i);
+    }
+    // the dropped logs are reported by the timer, without another log.
+    tokio::time::sleep(Duration::from_millis(600)).await;
+    check();
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use common::read_logs;
+use layla_log::*;
+
+fn setting() -> Setting {
+    Setting {
+        sampling: vec![
+            SampleRule::probability(LogLevel::Trace, 0.01),
+            SampleRule::every_nth(LogLevel::Debug, 100).module("sampling"),
+        ],
+        ..Default::default()
+    }
+}
+
+fn check() {
+    let logs = read_logs();
+    let count = |prefix: &str| logs.lines().filter(|line| line.contains(prefix)).count();
+    assert_eq!(count("info "), 10, "{logs}");
+    assert_eq!(count("warn "), 1, "{logs}");
+    assert_eq!(count("debug "), 10, "{logs}");
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn sampling() {
+    clean_log();
+    init(setting());
+
+    for i in 0..1000 {
+        trace!("trace {}", i);
+        debug!("debug {}", i);
+        info_every_n!(100, "info {}", i);
+        warn_once!("warn {}", i);
+    }
+    check();
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn sampling() {
+    clean_log().await;
+    init(setting()).await;
+
+    for i in 0..1000 {
+        trace!("trace {}", i);
+        debug!("debug {}", i);
+        info_every_n!(100, "info {}", i);
+        warn_once!("warn {}", i);
+    }
+    check();
+}
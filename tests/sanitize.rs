@@ -0,0 +1,59 @@
+use layla_log::*;
+
+fn setting() -> Setting {
+    Setting {
+        sanitize_messages: true,
+        ..Default::default()
+    }
+}
+
+fn read_logs() -> Vec<String> {
+    let mut lines = Vec::new();
+    for entry in std::fs::read_dir("./logs").unwrap() {
+        let logs = std::fs::read_to_string(entry.unwrap().path()).unwrap();
+        lines.extend(logs.lines().map(str::to_string));
+    }
+    lines
+}
+
+fn check(lines: &[String]) {
+    let forged = lines.iter().find(|line| line.contains("fake")).unwrap();
+    assert!(forged.contains("] | 2025-01-01 00:00:00.000 ERROR [main @ src/main.rs:1:1] fake"));
+    assert!(lines
+        .iter()
+        .any(|line| line.ends_with("] user: \\x1b[31mred\\x1b[0m")));
+    assert!(lines
+        .iter()
+        .any(|line| line.ends_with("] name: admin\\r\\u{202e}txt.exe\\u{85}")));
+    assert!(lines
+        .iter()
+        .all(|line| !line.contains('\x1b') && !line.contains('\r')));
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn sanitize() {
+    clean_log();
+    init(setting());
+
+    let input = "login\n2025-01-01 00:00:00.000 ERROR [main @ src/main.rs:1:1] fake";
+    warn!("user: {}", input);
+    info!("user: \x1b[31mred\x1b[0m");
+    info!("name: admin\r\u{202e}txt.exe\u{85}");
+
+    check(&read_logs());
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn sanitize() {
+    clean_log().await;
+    init(setting()).await;
+
+    let input = "login\n2025-01-01 00:00:00.000 ERROR [main @ src/main.rs:1:1] fake";
+    warn!("user: {}", input);
+    info!("user: \x1b[31mred\x1b[0m");
+    info!("name: admin\r\u{202e}txt.exe\u{85}");
+
+    check(&read_logs());
+}
@@ -0,0 +1,48 @@
+mod common;
+
+use common::read_logs;
+use layla_log::*;
+use std::time::Duration;
+
+fn setting() -> Setting {
+    Setting {
+        buffer_size: 1 << 20,
+        buffer_latency: Duration::from_secs(60),
+        shutdown_record: true,
+        ..Default::default()
+    }
+}
+
+fn check() {
+    let logs = read_logs();
+    assert_eq!(logs.lines().count(), 3);
+    assert!(logs.lines().nth(2).unwrap().ends_with("logger shutdown"));
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn shutdown() {
+    clean_log();
+    let guard = init_with_guard(setting());
+
+    info!("This is an info message");
+    warn!("This is a warning message");
+    assert_eq!(read_logs().lines().count(), 0);
+    drop(guard);
+
+    check();
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn shutdown() {
+    clean_log().await;
+    let guard = init_with_guard(setting()).await;
+
+    info!("This is an info message");
+    warn!("This is a warning message");
+    assert_eq!(read_logs().lines().count(), 0);
+    drop(guard);
+
+    check();
+}
@@ -0,0 +1,54 @@
+#![cfg(feature = "integrity")]
+
+mod common;
+
+use common::read_logs;
+use layla_log::*;
+
+const KEY: &[u8] = b"key";
+
+fn setting() -> Setting {
+    Setting {
+        signing_key: Some(SecretKey::new(KEY)),
+        signing_block: 10,
+        ..Default::default()
+    }
+}
+
+/// The logs of the unfinished block are signed when the logger is shut down.
+fn check() {
+    let logs = read_logs();
+    assert_eq!(logs.lines().count(), 3);
+    assert!(logs.lines().nth(2).unwrap().starts_with("#sig 0-1 "));
+    let report = verify_signatures("./logs", KEY).unwrap();
+    assert!(report.is_valid(), "{:?}", report.issues);
+    assert_eq!(report.verified, 2);
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn shutdown_signing() {
+    clean_log();
+    let guard = init_with_guard(setting());
+
+    info!("This is an info message");
+    warn!("This is a warning message");
+    assert!(!verify_signatures("./logs", KEY).unwrap().is_valid());
+    drop(guard);
+
+    check();
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn shutdown_signing() {
+    clean_log().await;
+    let guard = init_with_guard(setting()).await;
+
+    info!("This is an info message");
+    warn!("This is a warning message");
+    assert!(!verify_signatures("./logs", KEY).unwrap().is_valid());
+    drop(guard);
+
+    check();
+}
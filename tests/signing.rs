@@ -0,0 +1,107 @@
+#![cfg(feature = "integrity")]
+
+use layla_log::*;
+use std::path::PathBuf;
+
+const KEY: &[u8] = b"0123456789abcdef";
+
+fn setting() -> Setting {
+    Setting {
+        single_length: 3,
+        hash_chain: true,
+        signing_key: Some(SecretKey::new(KEY)),
+        signing_block: 2,
+        ..Default::default()
+    }
+}
+
+fn log_file(index: usize) -> PathBuf {
+    let mut files: Vec<PathBuf> = std::fs::read_dir("./logs")
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    files.sort();
+    files.remove(index)
+}
+
+fn check() {
+    let report = verify_signatures("./logs", KEY).unwrap();
+    assert_eq!(report.verified, 8);
+    assert!(report.is_valid(), "{:?}", report.issues);
+    assert_eq!(verify("./logs"), Ok(13));
+
+    // no log file in the directory
+    std::fs::create_dir_all("./logs/empty").unwrap();
+    let error = verify_signatures("./logs/empty", KEY).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+    std::fs::remove_dir("./logs/empty").unwrap();
+
+    let report = verify_signatures("./logs", b"wrong key").unwrap();
+    assert_eq!(report.verified, 0);
+    assert!(report
+        .issues
+        .iter()
+        .all(|issue| matches!(issue, SignatureIssue::Altered { .. })));
+
+    // edit a log in the second file
+    let file = log_file(1);
+    let content = std::fs::read_to_string(&file).unwrap();
+    std::fs::write(&file, content.replace("log 4", "log 5")).unwrap();
+    let report = verify_signatures("./logs", KEY).unwrap();
+    assert_eq!(
+        report.issues,
+        vec![SignatureIssue::Altered {
+            file: file.clone(),
+            line: 4
+        }]
+    );
+
+    // swap the first two logs of the second file
+    let mut lines: Vec<&str> = content.lines().collect();
+    lines.swap(1, 2);
+    std::fs::write(&file, lines.join("\n") + "\n").unwrap();
+    let report = verify_signatures("./logs", KEY).unwrap();
+    assert!(report.issues.contains(&SignatureIssue::Reordered {
+        file: file.clone(),
+        line: 3,
+        sequence: 3
+    }));
+
+    // remove the second file
+    std::fs::remove_file(&file).unwrap();
+    let report = verify_signatures("./logs", KEY).unwrap();
+    assert_eq!(
+        report.issues,
+        vec![SignatureIssue::Missing {
+            file: log_file(1),
+            line: 2,
+            first: 3,
+            last: 5
+        }]
+    );
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn signing() {
+    clean_log();
+    init(setting());
+
+    for i in 0..8 {
+        info!("log {}", i);
+    }
+
+    check();
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn signing() {
+    clean_log().await;
+    init(setting()).await;
+
+    for i in 0..8 {
+        info!("log {}", i);
+    }
+    check();
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use common::read_logs;
+use layla_log::*;
+
+/// Check the lines of the request marked by the id, in order, by their span paths and messages.
+fn check_request(logs: &str, id: &str) {
+    let lines: Vec<&str> = logs.lines().filter(|line| line.contains(id)).collect();
+    let expected = [
+        "] enter handle_request{".to_string(),
+        format!("] handle_request: request {} received", id),
+        "] handle_request: enter db_query{".to_string(),
+        format!("] handle_request > db_query: SELECT {}", id),
+        format!("] handle_request > db_query: query {} took", id),
+        "] handle_request: exit db_query{".to_string(),
+        format!("] handle_request: request {} handled", id),
+        "] exit handle_request{".to_string(),
+        format!("] outside of the span {}", id),
+    ];
+    assert_eq!(lines.len(), expected.len(), "{logs}");
+    for (line, expected) in lines.iter().zip(expected) {
+        assert!(line.contains(&expected), "{expected:?} in {logs}");
+    }
+}
+
+#[cfg(not(feature = "async"))]
+fn db_query(id: &str) {
+    let _span = span!(LogLevel::Debug, "db_query", id = id);
+    info!("SELECT {}", id);
+    // a detached log, which keeps the span path where it is recorded.
+    drop(timer!(LogLevel::Info, &format!("query {}", id)));
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn span() {
+    clean_log();
+
+    {
+        let _span = span!(LogLevel::Info, "handle_request", id = "#7", user = "layla");
+        info!("request #7 received");
+        db_query("#7");
+        info!("request #7 handled");
+    }
+    info!("outside of the span #7");
+
+    check_request(&read_logs(), "#7");
+}
+
+#[cfg(feature = "async")]
+async fn db_query(id: &str) {
+    let span = span!(LogLevel::Debug, "db_query", id = id);
+    info!("SELECT {}", id);
+    // a detached log, which keeps the span path where it is recorded.
+    drop(timer!(LogLevel::Info, &format!("query {}", id)));
+    span.exit().await;
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn span() {
+    clean_log().await;
+
+    let handles: Vec<_> = (0..2)
+        .map(|i| {
+            tokio::spawn(async move {
+                let id = format!("#{}", i);
+                let span = span!(LogLevel::Info, "handle_request", id = id);
+                info!("request {} received", id);
+                db_query(&id).await;
+                info!("request {} handled", id);
+                span.exit().await;
+                info!("outside of the span {}", id);
+            })
+        })
+        .collect();
+    for handle in handles {
+        handle.await.unwrap();
+    }
+
+    let logs = read_logs();
+    check_request(&logs, "#0");
+    check_request(&logs, "#1");
+}
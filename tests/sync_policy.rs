@@ -0,0 +1,38 @@
+mod common;
+
+use common::read_logs;
+use layla_log::*;
+
+fn setting() -> Setting {
+    Setting {
+        single_length: 2,
+        sync_policy: SyncPolicy::EveryRecords(2),
+        ..Default::default()
+    }
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn sync_policy() {
+    clean_log();
+    init(setting());
+
+    info!("This is an info message");
+    error!("This is an error message");
+    warn!("This is a warning message");
+    sync();
+    assert_eq!(read_logs().lines().count(), 3);
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn sync_policy() {
+    clean_log().await;
+    init(setting()).await;
+
+    info!("This is an info message");
+    error!("This is an error message");
+    warn!("This is a warning message");
+    sync().await;
+    assert_eq!(read_logs().lines().count(), 3);
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use common::read_logs;
+use layla_log::*;
+
+fn setting() -> Setting {
+    Setting {
+        time_format: TimeFormat::Rfc3339(Precision::Micros),
+        time_zone: TimeZone::minutes(5 * 60 + 30),
+        ..Default::default()
+    }
+}
+
+/// The time is an RFC 3339 timestamp with microseconds and the offset of the time zone.
+fn check() {
+    let logs = read_logs();
+    let lines: Vec<&str> = logs.lines().collect();
+    assert_eq!(lines.len(), 1, "{logs}");
+    let time = lines[0].split(' ').next().unwrap();
+    let parsed = chrono::DateTime::parse_from_rfc3339(time).unwrap();
+    assert_eq!(
+        parsed.offset().local_minus_utc(),
+        (5 * 60 + 30) * 60,
+        "{logs}"
+    );
+    assert!(time.ends_with("+05:30"), "{logs}");
+    let (_, fraction) = time.trim_end_matches("+05:30").split_once('.').unwrap();
+    assert_eq!(fraction.len(), 6, "{logs}");
+    assert!(lines[0].ends_with("This is an info message"), "{logs}");
+}
+
+#[test]
+fn validate() {
+    assert!(TimeFormat::Rfc3339(Precision::Nanos).validate().is_ok());
+    assert!(TimeFormat::Custom("%d/%m/%Y %H:%M:%S%.6f %z".to_string())
+        .validate()
+        .is_ok());
+    assert!(TimeFormat::Custom("%Y-%m-%d %Q".to_string())
+        .validate()
+        .is_err());
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn rfc3339() {
+    clean_log();
+    init(setting());
+
+    info!("This is an info message");
+
+    check();
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn rfc3339() {
+    clean_log().await;
+    init(setting()).await;
+
+    info!("This is an info message");
+
+    check();
+}
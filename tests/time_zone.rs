@@ -1,33 +1,66 @@
+mod common;
+
+use common::read_logs;
 use layla_log::*;
 
+fn setting() -> Setting {
+    Setting {
+        time_zone: TimeZone::minutes(5 * 60 + 30),
+        time_detailed_display: true,
+        ..Default::default()
+    }
+}
+
+/// Each log shows the offset of the time zone.
+fn check() {
+    let logs = read_logs();
+    assert!(logs.lines().count() >= 3, "{logs}");
+    assert!(
+        logs.lines().all(|line| line.contains(" (+05:30) ")),
+        "{logs}"
+    );
+}
+
 #[cfg(not(feature = "async"))]
 #[test]
 fn write_in() {
     clean_log();
-    init(Setting {
-        time_zone: 1,
-        ..Default::default()
-    });
-    
+    init(setting());
+
     error!("This is an error message");
     warn!("This is a warning message");
     info!("This is an info message");
     debug!("This is a debug message");
     trace!("This is a trace message");
+
+    check();
 }
 
 #[cfg(feature = "async")]
 #[tokio::test]
 async fn write_in() {
     clean_log().await;
-    init(Setting {
-        time_zone: 1,
-        ..Default::default()
-    }).await;
+    init(setting()).await;
 
     error!("This is an error message");
     warn!("This is a warning message");
     info!("This is an info message");
     debug!("This is a debug message");
     trace!("This is a trace message");
+
+    check();
+}
+
+#[test]
+fn validate() {
+    assert!(TimeZone::minutes(5 * 60 + 45).validate().is_ok());
+    assert!(TimeZone::Local.validate().is_ok());
+    assert!(TimeZone::hours(24).validate().is_err());
+}
+
+#[cfg(feature = "chrono-tz")]
+#[test]
+fn named() {
+    assert!(TimeZone::named("Asia/Kathmandu").is_ok());
+    assert!(TimeZone::named("Mars/Olympus_Mons").is_err());
 }
@@ -0,0 +1,67 @@
+mod common;
+
+use common::read_logs;
+use layla_log::*;
+use std::time::Duration;
+
+fn check(logs: &str) {
+    let lines: Vec<&str> = logs.lines().collect();
+    assert_eq!(lines.len(), 6, "{}", logs);
+    assert!(lines[0].contains("INFO") && lines[0].contains("sum took"));
+    assert!(lines[1].contains("WARN") && lines[1].contains("sleep took"));
+    assert!(lines[2].contains("INFO") && lines[2].contains("product took"));
+    assert!(lines[3].contains("INFO") && lines[3].ends_with("product is 120"));
+    assert!(lines[4].contains("DEBUG") && lines[4].contains("whole test took"));
+    assert!(lines[5].ends_with("done"));
+}
+
+#[cfg(not(feature = "async"))]
+#[test]
+fn timer() {
+    clean_log();
+
+    let sum = time_block!(LogLevel::Info, "sum", { (0..1000).sum::<u64>() });
+    assert_eq!(sum, 499500);
+
+    time_block!(LogLevel::Info, "sleep", Duration::from_millis(1) => LogLevel::Warn, {
+        std::thread::sleep(Duration::from_millis(5));
+    });
+
+    // the arguments record a log themselves, which must not wait for the lock of the outer log.
+    info!(
+        "product is {}",
+        time_block!(LogLevel::Info, "product", { (1..=5).product::<u64>() })
+    );
+
+    let timer = timer!(LogLevel::Debug, "whole test").slow(Duration::from_secs(10), LogLevel::Warn);
+    drop(timer);
+    info!("done");
+
+    check(&read_logs());
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn timer() {
+    clean_log().await;
+
+    let sum = time_block!(LogLevel::Info, "sum", { (0..1000).sum::<u64>() });
+    assert_eq!(sum, 499500);
+
+    time_block!(LogLevel::Info, "sleep", Duration::from_millis(1) => LogLevel::Warn, {
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    });
+
+    // the arguments record a log themselves, which must not wait for the lock of the outer log.
+    info!(
+        "product is {}",
+        time_block!(LogLevel::Info, "product", { (1..=5).product::<u64>() })
+    );
+
+    let timer = timer!(LogLevel::Debug, "whole test").slow(Duration::from_secs(10), LogLevel::Warn);
+    drop(timer);
+    // the log of the timer is queued, and written before the next log.
+    info!("done");
+
+    check(&read_logs());
+}